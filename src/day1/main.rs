@@ -1,23 +1,41 @@
+use std::env;
 use std::fs::File;
 use std::io::{self, BufRead};
 
-fn read_calories(file: &str) -> io::Result<Vec<u32>> {
+mod partition;
+mod query;
+
+use partition::Method;
+
+// Reads the items carried by each elf, one list per elf
+fn read_items(file: &str) -> io::Result<Vec<Vec<u32>>> {
     let f = File::open(file)?;
 
-    let mut count = 0u32;
-    let mut calories = Vec::new();
-    for lres in io::BufReader::new(f).lines() {
-        if let Ok(line) = lres {
-            let trimmed_line = line.trim();
-
-            if trimmed_line.is_empty() {
-                calories.push(count);
-                count = 0;
-            } else {
-                count += trimmed_line.parse::<u32>().expect("Expected integer!");
-            }
+    let mut items = Vec::new();
+    let mut elves = Vec::new();
+    for line in io::BufReader::new(f).lines().map_while(Result::ok) {
+        let trimmed_line = line.trim();
+
+        if trimmed_line.is_empty() {
+            elves.push(items);
+            items = Vec::new();
+        } else {
+            items.push(trimmed_line.parse::<u32>().expect("Expected integer!"));
         }
     }
+
+    // the last elf is not necessarily followed by an empty line
+    if !items.is_empty() {
+        elves.push(items);
+    }
+    Ok(elves)
+}
+
+fn read_calories(file: &str) -> io::Result<Vec<u32>> {
+    let mut calories: Vec<u32> = read_items(file)?
+        .iter()
+        .map(|items| items.iter().sum())
+        .collect();
     calories.sort();
     Ok(calories)
 }
@@ -30,7 +48,108 @@ fn solve_pt2(calories: &[u32]) -> u32 {
     calories.iter().rev().take(3).sum()
 }
 
+fn print_balance(file: &str, num_elves: usize, method: Method) {
+    let elves = read_items(file).unwrap();
+    let items: Vec<u32> = elves.into_iter().flatten().collect();
+
+    let assignment = partition::balance(&items, num_elves, method);
+    println!(
+        "Distributed {} items among {} elves, the maximum load is {} calories",
+        items.len(),
+        num_elves,
+        assignment.max_load()
+    );
+
+    for (elf, load) in assignment.loads.iter().enumerate() {
+        let elf_items: Vec<String> = assignment
+            .items_of(elf)
+            .iter()
+            .map(|idx| items[*idx].to_string())
+            .collect();
+        println!(
+            "Elf {}: {} calories [{}]",
+            elf + 1,
+            load,
+            elf_items.join(", ")
+        );
+    }
+}
+
+fn print_give(file: &str, elf: usize, calories: u32) {
+    let elves = read_items(file).unwrap();
+    let items = elf
+        .checked_sub(1)
+        .and_then(|idx| elves.get(idx))
+        .expect("Unknown elf!");
+
+    match query::subset_with_sum(items, calories) {
+        Some(subset) => {
+            let given: Vec<String> = subset.iter().map(|idx| items[*idx].to_string()).collect();
+            println!(
+                "Elf {} can hand over {} calories: [{}]",
+                elf,
+                calories,
+                given.join(", ")
+            );
+        }
+        None => println!("Elf {} can't hand over exactly {} calories", elf, calories),
+    }
+}
+
+fn print_dethrone(file: &str) {
+    let elves = read_items(file).unwrap();
+
+    match query::dethrone_top_elf(&elves) {
+        Some((elf, give_away)) => {
+            let items = &elves[elf];
+            let given: Vec<String> = give_away
+                .iter()
+                .map(|idx| items[*idx].to_string())
+                .collect();
+            let total: u32 = give_away.iter().map(|idx| items[*idx]).sum();
+            println!(
+                "Elf {} has to give away {} calories: [{}]",
+                elf + 1,
+                total,
+                given.join(", ")
+            );
+        }
+        None => println!("There is no single elf carrying the most calories"),
+    }
+}
+
 fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() > 1 && args[1] == "balance" {
+        // day1 balance <elves> [exact|greedy|kk|auto]
+        let num_elves = args
+            .get(2)
+            .map(|s| s.parse::<usize>().expect("Expected number of elves!"))
+            .unwrap_or(3);
+        let method = args
+            .get(3)
+            .map(|s| Method::build(s).expect("Unknown method!"))
+            .unwrap_or(Method::Auto);
+
+        print_balance("data/day1/input", num_elves, method);
+        return;
+    }
+
+    if args.len() > 3 && args[1] == "give" {
+        // day1 give <elf> <calories>, elves are numbered from one
+        let elf = args[2].parse::<usize>().expect("Expected elf number!");
+        let calories = args[3].parse::<u32>().expect("Expected calories!");
+
+        print_give("data/day1/input", elf, calories);
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "dethrone" {
+        print_dethrone("data/day1/input");
+        return;
+    }
+
     let calories = read_calories("data/day1/input").unwrap();
 
     let solution_pt1 = solve_pt1(&calories);
@@ -69,6 +188,25 @@ mod tests {
         assert_eq!(calories, read_calories("data/day1/test").unwrap());
     }
 
+    #[test]
+    fn read_items_test() {
+        let elves = read_items("data/day1/test").unwrap();
+        assert_eq!(elves.len(), 5);
+        assert_eq!(elves[0], vec![1000, 2000, 3000]);
+        assert_eq!(elves[4], vec![10000]);
+    }
+
+    #[test]
+    fn read_without_trailing_empty_line_test() {
+        // the last elf used to be dropped without the empty line after it
+        let elves = read_items("data/day1/test_no_trailing").unwrap();
+        assert_eq!(elves, read_items("data/day1/test").unwrap());
+        assert_eq!(
+            read_calories("data/day1/test_no_trailing").unwrap(),
+            read_calories("data/day1/test").unwrap()
+        );
+    }
+
     #[test]
     fn edge_case_test() {
         assert_eq!(solve_pt1(&[]), 0);
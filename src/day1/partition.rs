@@ -0,0 +1,329 @@
+// Redistributes the snacks of all elves such that the heaviest load is as
+// small as possible (multiway number partitioning).
+
+use std::cmp::{Ordering, Reverse};
+use std::collections::BinaryHeap;
+
+// Up to this many items Method::Auto searches for the optimal assignment
+const EXACT_LIMIT: usize = 24;
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum Method {
+    Exact,
+    Greedy,
+    KarmarkarKarp,
+    Auto,
+}
+
+impl Method {
+    pub fn build(name: &str) -> Option<Method> {
+        match name {
+            "exact" => Some(Method::Exact),
+            "greedy" => Some(Method::Greedy),
+            "kk" => Some(Method::KarmarkarKarp),
+            "auto" => Some(Method::Auto),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Assignment {
+    // the elf carrying each item
+    pub owners: Vec<usize>,
+    // the total calories carried by each elf
+    pub loads: Vec<u32>,
+}
+
+impl Assignment {
+    fn build(items: &[u32], owners: Vec<usize>, num_elves: usize) -> Assignment {
+        let mut loads = vec![0; num_elves];
+        for (item, owner) in items.iter().zip(&owners) {
+            loads[*owner] += item;
+        }
+        Assignment { owners, loads }
+    }
+
+    pub fn max_load(&self) -> u32 {
+        self.loads.iter().copied().max().unwrap_or(0)
+    }
+
+    // Indices of all items carried by the given elf
+    pub fn items_of(&self, elf: usize) -> Vec<usize> {
+        self.owners
+            .iter()
+            .enumerate()
+            .filter(|(_, owner)| **owner == elf)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+}
+
+pub fn balance(items: &[u32], num_elves: usize, method: Method) -> Assignment {
+    assert!(num_elves > 0, "Expected at least one elf!");
+
+    match method {
+        Method::Exact => balance_exact(items, num_elves),
+        Method::Greedy => balance_greedy(items, num_elves),
+        Method::KarmarkarKarp => balance_karmarkar_karp(items, num_elves),
+        Method::Auto => {
+            if items.len() <= EXACT_LIMIT {
+                balance_exact(items, num_elves)
+            } else {
+                balance_heuristic(items, num_elves)
+            }
+        }
+    }
+}
+
+// Item indices ordered by decreasing calories
+fn sorted_indices(items: &[u32]) -> Vec<usize> {
+    let mut indices: Vec<usize> = (0..items.len()).collect();
+    indices.sort_by(|a, b| items[*b].cmp(&items[*a]));
+    indices
+}
+
+// Longest processing time first: the next biggest item always goes to the
+// elf with the smallest load so far
+fn balance_greedy(items: &[u32], num_elves: usize) -> Assignment {
+    let mut loads = vec![0u32; num_elves];
+    let mut owners = vec![0; items.len()];
+
+    for idx in sorted_indices(items) {
+        let (elf, _) = loads
+            .iter()
+            .enumerate()
+            .min_by_key(|(_, load)| **load)
+            .unwrap();
+        owners[idx] = elf;
+        loads[elf] += items[idx];
+    }
+    Assignment { owners, loads }
+}
+
+// A partial solution of the Karmarkar-Karp heuristic, i.e. one subset (sum and
+// item indices) per elf, sorted by decreasing sum
+#[derive(PartialEq, Eq)]
+struct Partial {
+    subsets: Vec<(u32, Vec<usize>)>,
+}
+
+impl Partial {
+    fn spread(&self) -> u32 {
+        self.subsets[0].0 - self.subsets[self.subsets.len() - 1].0
+    }
+
+    // Combines the largest subset of one with the smallest subset of the other
+    fn merge(self, other: Partial) -> Partial {
+        let mut subsets: Vec<(u32, Vec<usize>)> = self
+            .subsets
+            .into_iter()
+            .zip(other.subsets.into_iter().rev())
+            .map(|((lsum, mut lidx), (rsum, ridx))| {
+                lidx.extend(ridx);
+                (lsum + rsum, lidx)
+            })
+            .collect();
+        subsets.sort_by_key(|subset| Reverse(subset.0));
+        Partial { subsets }
+    }
+}
+
+impl Ord for Partial {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.spread()
+            .cmp(&other.spread())
+            .then_with(|| self.subsets.cmp(&other.subsets))
+    }
+}
+
+impl PartialOrd for Partial {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+// Largest differencing method: repeatedly merges the two partial solutions
+// with the biggest spread until only one is left
+fn balance_karmarkar_karp(items: &[u32], num_elves: usize) -> Assignment {
+    let mut heap: BinaryHeap<Partial> = items
+        .iter()
+        .enumerate()
+        .map(|(idx, item)| {
+            let mut subsets = vec![(0, Vec::new()); num_elves];
+            subsets[0] = (*item, vec![idx]);
+            Partial { subsets }
+        })
+        .collect();
+
+    while heap.len() > 1 {
+        let first = heap.pop().unwrap();
+        let second = heap.pop().unwrap();
+        heap.push(first.merge(second));
+    }
+
+    let mut owners = vec![0; items.len()];
+    if let Some(partial) = heap.pop() {
+        for (elf, (_, indices)) in partial.subsets.iter().enumerate() {
+            for idx in indices {
+                owners[*idx] = elf;
+            }
+        }
+    }
+    Assignment::build(items, owners, num_elves)
+}
+
+// The better result of both heuristics
+fn balance_heuristic(items: &[u32], num_elves: usize) -> Assignment {
+    let greedy = balance_greedy(items, num_elves);
+    let kk = balance_karmarkar_karp(items, num_elves);
+
+    if kk.max_load() < greedy.max_load() {
+        kk
+    } else {
+        greedy
+    }
+}
+
+// Depth-first search over the items sorted by decreasing calories
+struct Search<'a> {
+    sizes: &'a [u32],
+    loads: Vec<u32>,
+    current: Vec<usize>,
+    best_load: u32,
+    best: Option<Vec<usize>>,
+    lower_bound: u32,
+}
+
+impl Search<'_> {
+    // Returns true as soon as an assignment reaching the lower bound is found
+    fn descend(&mut self, pos: usize) -> bool {
+        if pos == self.sizes.len() {
+            // every branch taken is below the best load found so far
+            self.best_load = self.loads.iter().copied().max().unwrap_or(0);
+            self.best = Some(self.current.clone());
+            return self.best_load <= self.lower_bound;
+        }
+
+        let size = self.sizes[pos];
+        for elf in 0..self.loads.len() {
+            // elves with equal loads are interchangeable, only try the first one
+            if self.loads[..elf].contains(&self.loads[elf]) {
+                continue;
+            }
+            if self.loads[elf] + size >= self.best_load {
+                continue;
+            }
+
+            self.loads[elf] += size;
+            self.current[pos] = elf;
+            let done = self.descend(pos + 1);
+            self.loads[elf] -= size;
+
+            if done {
+                return true;
+            }
+        }
+        false
+    }
+}
+
+// Branch and bound, starting from the heuristic solution as upper bound
+fn balance_exact(items: &[u32], num_elves: usize) -> Assignment {
+    let heuristic = balance_heuristic(items, num_elves);
+
+    let total: u32 = items.iter().sum();
+    let largest = items.iter().copied().max().unwrap_or(0);
+    let lower_bound = largest.max(total.div_ceil(num_elves as u32));
+
+    if heuristic.max_load() <= lower_bound {
+        return heuristic;
+    }
+
+    let order = sorted_indices(items);
+    let sizes: Vec<u32> = order.iter().map(|idx| items[*idx]).collect();
+
+    let mut search = Search {
+        sizes: &sizes,
+        loads: vec![0; num_elves],
+        current: vec![0; items.len()],
+        best_load: heuristic.max_load(),
+        best: None,
+        lower_bound,
+    };
+    search.descend(0);
+
+    match search.best {
+        Some(sorted_owners) => {
+            let mut owners = vec![0; items.len()];
+            for (pos, idx) in order.iter().enumerate() {
+                owners[*idx] = sorted_owners[pos];
+            }
+            Assignment::build(items, owners, num_elves)
+        }
+        None => heuristic,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check_consistent(items: &[u32], assignment: &Assignment) {
+        let rebuilt = Assignment::build(items, assignment.owners.clone(), assignment.loads.len());
+        assert_eq!(rebuilt.loads, assignment.loads);
+        assert_eq!(
+            assignment.loads.iter().sum::<u32>(),
+            items.iter().sum::<u32>()
+        );
+    }
+
+    #[test]
+    fn test_methods() {
+        let items = [8, 7, 6, 5, 4];
+
+        let greedy = balance(&items, 2, Method::Greedy);
+        let kk = balance(&items, 2, Method::KarmarkarKarp);
+        let exact = balance(&items, 2, Method::Exact);
+
+        assert_eq!(greedy.max_load(), 17);
+        assert_eq!(kk.max_load(), 16);
+        assert_eq!(exact.max_load(), 15);
+
+        for assignment in [greedy, kk, exact] {
+            check_consistent(&items, &assignment);
+        }
+    }
+
+    #[test]
+    fn test_exact_three_way() {
+        let items = [4, 5, 6, 7, 8, 3, 2, 1, 9];
+        let exact = balance(&items, 3, Method::Exact);
+        assert_eq!(exact.max_load(), 15);
+        check_consistent(&items, &exact);
+
+        let auto = balance(&items, 3, Method::Auto);
+        assert_eq!(auto.max_load(), 15);
+    }
+
+    #[test]
+    fn test_items_of() {
+        let items = [1000, 2000, 3000, 4000];
+        let exact = balance(&items, 2, Method::Exact);
+        assert_eq!(exact.max_load(), 5000);
+
+        let first = exact.items_of(0);
+        let second = exact.items_of(1);
+        assert_eq!(first.len() + second.len(), items.len());
+        assert_eq!(first.iter().map(|idx| items[*idx]).sum::<u32>(), 5000);
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        for method in [Method::Exact, Method::Greedy, Method::KarmarkarKarp] {
+            assert_eq!(balance(&[], 3, method).max_load(), 0);
+            assert_eq!(balance(&[5, 3], 4, method).max_load(), 5);
+            assert_eq!(balance(&[5, 3], 1, method).max_load(), 8);
+        }
+    }
+}
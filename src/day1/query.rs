@@ -0,0 +1,162 @@
+// Subset-sum queries over the items carried by a single elf.
+
+// All sums up to a capacity which can be reached by a subset of the items.
+// For every sum the index of the item that reached it first is kept, it is
+// larger than the indices of the items used for the remainder, which allows
+// walking back to the subset.
+struct Reachable {
+    reached: Vec<bool>,
+    last_item: Vec<Option<usize>>,
+}
+
+impl Reachable {
+    fn build(items: &[u32], capacity: u32) -> Reachable {
+        let capacity = capacity as usize;
+        let mut last_item = vec![None; capacity + 1];
+        let mut reached = vec![false; capacity + 1];
+        reached[0] = true;
+
+        for (idx, item) in items.iter().enumerate() {
+            let item = *item as usize;
+            if item == 0 || item > capacity {
+                continue;
+            }
+
+            for sum in (item..=capacity).rev() {
+                if !reached[sum] && reached[sum - item] {
+                    reached[sum] = true;
+                    last_item[sum] = Some(idx);
+                }
+            }
+        }
+        Reachable { reached, last_item }
+    }
+
+    fn is_reachable(&self, sum: u32) -> bool {
+        self.reached[sum as usize]
+    }
+
+    // Item indices, in increasing order, adding up to the given reachable sum
+    fn subset(&self, items: &[u32], sum: u32) -> Vec<usize> {
+        let mut subset = Vec::new();
+        let mut sum = sum as usize;
+
+        while sum > 0 {
+            let idx = self.last_item[sum].expect("Expected reachable sum!");
+            subset.push(idx);
+            sum -= items[idx] as usize;
+        }
+        subset.reverse();
+        subset
+    }
+}
+
+// Items (as indices) which add up to exactly `calories`, if there are any
+pub fn subset_with_sum(items: &[u32], calories: u32) -> Option<Vec<usize>> {
+    let total: u32 = items.iter().sum();
+    if calories > total {
+        return None;
+    }
+
+    let reachable = Reachable::build(items, calories);
+    if reachable.is_reachable(calories) {
+        Some(reachable.subset(items, calories))
+    } else {
+        None
+    }
+}
+
+// The items to give away such that the remaining calories are strictly less
+// than `limit`, giving away as few calories as possible
+pub fn items_to_give_away(items: &[u32], limit: u32) -> Option<Vec<usize>> {
+    if limit == 0 {
+        return None;
+    }
+
+    // keep the heaviest subset below the limit, give away everything else
+    let capacity = limit - 1;
+    let reachable = Reachable::build(items, capacity);
+    let kept_sum = (0..=capacity)
+        .rev()
+        .find(|sum| reachable.is_reachable(*sum))
+        .unwrap_or(0);
+
+    let kept = reachable.subset(items, kept_sum);
+    Some((0..items.len()).filter(|idx| !kept.contains(idx)).collect())
+}
+
+// The elf carrying the most calories and the items it has to give away to drop
+// below the runner-up. None if there are less than two elves or a tie.
+pub fn dethrone_top_elf(elves: &[Vec<u32>]) -> Option<(usize, Vec<usize>)> {
+    let mut totals: Vec<(u32, usize)> = elves
+        .iter()
+        .enumerate()
+        .map(|(elf, items)| (items.iter().sum(), elf))
+        .collect();
+    totals.sort();
+
+    let (top_calories, top_elf) = *totals.last()?;
+    let (runner_up, _) = *totals.iter().rev().nth(1)?;
+    if top_calories == runner_up {
+        return None;
+    }
+
+    let give_away = items_to_give_away(&elves[top_elf], runner_up)?;
+    Some((top_elf, give_away))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sum_of(items: &[u32], indices: &[usize]) -> u32 {
+        indices.iter().map(|idx| items[*idx]).sum()
+    }
+
+    #[test]
+    fn test_subset_with_sum() {
+        let items = [3000, 5000, 1000, 4000];
+
+        let subset = subset_with_sum(&items, 8000).unwrap();
+        assert_eq!(sum_of(&items, &subset), 8000);
+
+        assert_eq!(subset_with_sum(&items, 0), Some(vec![]));
+        assert_eq!(subset_with_sum(&items, 13000), Some(vec![0, 1, 2, 3]));
+        assert_eq!(subset_with_sum(&items, 2000), None);
+        assert_eq!(subset_with_sum(&items, 14000), None);
+    }
+
+    #[test]
+    fn test_items_to_give_away() {
+        let items = [3000, 5000, 1000, 4000];
+
+        // keep 9000, i.e. give away as little as 4000
+        let give_away = items_to_give_away(&items, 9500).unwrap();
+        assert_eq!(sum_of(&items, &give_away), 4000);
+
+        // strictly less than the limit
+        let give_away = items_to_give_away(&items, 9000).unwrap();
+        assert_eq!(sum_of(&items, &give_away), 5000);
+
+        assert_eq!(items_to_give_away(&items, 20000), Some(vec![]));
+        assert_eq!(items_to_give_away(&items, 0), None);
+    }
+
+    #[test]
+    fn test_dethrone_top_elf() {
+        let elves = vec![
+            vec![1000, 2000, 3000],
+            vec![4000],
+            vec![5000, 6000],
+            vec![7000, 8000, 9000],
+            vec![10000],
+        ];
+
+        let (elf, give_away) = dethrone_top_elf(&elves).unwrap();
+        assert_eq!(elf, 3);
+        assert_eq!(give_away, vec![0, 1]);
+
+        assert_eq!(dethrone_top_elf(&elves[..1]), None);
+        assert_eq!(dethrone_top_elf(&[vec![1, 2], vec![3]]), None);
+    }
+}
@@ -20,7 +20,7 @@ impl Op {
 }
 
 fn parse_ops(input: &str) -> Vec<Op> {
-    input.lines().map(|l| Op::build(l)).collect()
+    input.lines().map(Op::build).collect()
 }
 
 fn run_ops(ops: Vec<Op>) -> Vec<i32> {
@@ -60,7 +60,7 @@ fn draw(xs: &[i32]) {
         let line: String = (0..WIDTH)
             .map(|x| {
                 (xs[y * WIDTH + x + 1] - 1) <= (x as i32)
-             && (xs[y * WIDTH + x + 1] + 1) >= (x as i32)
+                    && (xs[y * WIDTH + x + 1] + 1) >= (x as i32)
             })
             .map(|f| if f { '#' } else { '.' })
             .collect();
@@ -0,0 +1,127 @@
+// Statistics of the elf's moves and a best response based on them. The elf is
+// modelled as a first-order Markov chain: the next shape only depends on the
+// previous one.
+
+use super::{score_round, shape_idx, HandShape};
+
+const SHAPES: [HandShape; 3] = [HandShape::Rock, HandShape::Paper, HandShape::Scissors];
+
+// Probability distribution over the three shapes
+pub type Distribution = [f64; 3];
+
+pub fn frequencies(moves: &[HandShape]) -> Distribution {
+    let mut counts = [0.0; 3];
+    for shape in moves {
+        counts[shape_idx(*shape)] += 1.0;
+    }
+
+    let total = moves.len() as f64;
+    if total == 0.0 {
+        return [1.0 / 3.0; 3];
+    }
+    counts.map(|c| c / total)
+}
+
+// row: previous shape
+// col: next shape
+// Shapes which are never followed by another fall back to the frequencies.
+pub fn transitions(moves: &[HandShape]) -> [Distribution; 3] {
+    let mut counts = [[0.0; 3]; 3];
+    for pair in moves.windows(2) {
+        counts[shape_idx(pair[0])][shape_idx(pair[1])] += 1.0;
+    }
+
+    let fallback = frequencies(moves);
+    counts.map(|row| {
+        let total: f64 = row.iter().sum();
+        if total == 0.0 {
+            fallback
+        } else {
+            row.map(|c| c / total)
+        }
+    })
+}
+
+// The predicted distribution of every move given the previous one
+pub fn predictions(moves: &[HandShape]) -> Vec<Distribution> {
+    let first = frequencies(moves);
+    let transitions = transitions(moves);
+
+    (0..moves.len())
+        .map(|round| {
+            if round == 0 {
+                first
+            } else {
+                transitions[shape_idx(moves[round - 1])]
+            }
+        })
+        .collect()
+}
+
+pub fn expected_score(my_shape: HandShape, prediction: &Distribution) -> f64 {
+    SHAPES
+        .iter()
+        .zip(prediction)
+        .map(|(elf_shape, p)| p * score_round(*elf_shape, my_shape) as f64)
+        .sum()
+}
+
+// The shape maximizing the expected score against the predicted elf
+pub fn best_response(prediction: &Distribution) -> HandShape {
+    let mut best = SHAPES[0];
+    for shape in SHAPES {
+        if expected_score(shape, prediction) > expected_score(best, prediction) {
+            best = shape;
+        }
+    }
+    best
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_frequencies() {
+        let moves = [
+            HandShape::Rock,
+            HandShape::Rock,
+            HandShape::Paper,
+            HandShape::Rock,
+        ];
+        assert_eq!(frequencies(&moves), [0.75, 0.25, 0.0]);
+        assert_eq!(frequencies(&[]), [1.0 / 3.0; 3]);
+    }
+
+    #[test]
+    fn test_transitions() {
+        let moves = [
+            HandShape::Rock,
+            HandShape::Paper,
+            HandShape::Rock,
+            HandShape::Rock,
+        ];
+        let t = transitions(&moves);
+        assert_eq!(t[0], [0.5, 0.5, 0.0]);
+        assert_eq!(t[1], [1.0, 0.0, 0.0]);
+        // scissors never occurs
+        assert_eq!(t[2], frequencies(&moves));
+    }
+
+    #[test]
+    fn test_best_response() {
+        assert_eq!(best_response(&[1.0, 0.0, 0.0]), HandShape::Paper);
+        assert_eq!(best_response(&[0.0, 0.0, 1.0]), HandShape::Rock);
+
+        // an elf cycling rock, paper, scissors is fully predictable
+        let moves: Vec<HandShape> = (0..30).map(|i| SHAPES[i % 3]).collect();
+        let predictions = predictions(&moves);
+        for round in 1..moves.len() {
+            let my_shape = best_response(&predictions[round]);
+            assert_eq!(
+                score_round(moves[round], my_shape),
+                shape_idx(my_shape) as i32 + 7
+            );
+        }
+    }
+}
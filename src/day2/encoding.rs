@@ -0,0 +1,169 @@
+// Maps the tokens of the strategy guide to shapes and results. The encoding
+// is described by lines of the form `<column> <token> <meaning>`, e.g.
+//
+//   elf A rock
+//   me X rock
+//   result X lose
+//
+// Empty lines and lines starting with '#' are ignored.
+
+use std::collections::HashMap;
+use std::fs;
+
+use super::rules::ExpectedResult;
+use super::HandShape;
+
+pub struct Encoding {
+    elf_shapes: HashMap<String, HandShape>,
+    my_shapes: HashMap<String, HandShape>,
+    results: HashMap<String, ExpectedResult>,
+}
+
+fn parse_result(name: &str) -> Option<ExpectedResult> {
+    match name.to_lowercase().as_str() {
+        "lose" => Some(ExpectedResult::Lose),
+        "draw" => Some(ExpectedResult::Draw),
+        "win" => Some(ExpectedResult::Win),
+        _ => None,
+    }
+}
+
+impl Encoding {
+    fn empty() -> Encoding {
+        Encoding {
+            elf_shapes: HashMap::new(),
+            my_shapes: HashMap::new(),
+            results: HashMap::new(),
+        }
+    }
+
+    // A/B/C and X/Y/Z as given by the puzzle
+    pub fn standard() -> Encoding {
+        Encoding::parse(
+            "elf A rock\nelf B paper\nelf C scissors\n\
+             me X rock\nme Y paper\nme Z scissors\n\
+             result X lose\nresult Y draw\nresult Z win",
+        )
+        .unwrap()
+    }
+
+    pub fn parse(text: &str) -> Result<Encoding, String> {
+        let mut encoding = Encoding::empty();
+        for line in text.lines() {
+            encoding.add(line)?;
+        }
+        Ok(encoding)
+    }
+
+    pub fn load(file: &str) -> Result<Encoding, String> {
+        let text = fs::read_to_string(file).map_err(|e| format!("{}: {}", file, e))?;
+        Encoding::parse(&text)
+    }
+
+    // Adds (or overrides) a single `<column> <token> <meaning>` entry
+    pub fn add(&mut self, line: &str) -> Result<(), String> {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            return Ok(());
+        }
+
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        if parts.len() != 3 {
+            return Err(format!("Expected <column> <token> <meaning>: {}", line));
+        }
+        let (column, token, meaning) = (parts[0], parts[1].to_string(), parts[2]);
+
+        let unknown_shape = || format!("Unknown shape '{}'", meaning);
+        match column {
+            "elf" => {
+                let shape = HandShape::build(meaning).ok_or_else(unknown_shape)?;
+                self.elf_shapes.insert(token, shape);
+            }
+            "me" => {
+                let shape = HandShape::build(meaning).ok_or_else(unknown_shape)?;
+                self.my_shapes.insert(token, shape);
+            }
+            "result" => {
+                let result =
+                    parse_result(meaning).ok_or_else(|| format!("Unknown result '{}'", meaning))?;
+                self.results.insert(token, result);
+            }
+            _ => return Err(format!("Unknown column '{}'", column)),
+        }
+        Ok(())
+    }
+
+    pub fn elf_shape(&self, token: &str) -> Option<HandShape> {
+        self.elf_shapes.get(token).copied()
+    }
+
+    pub fn my_shape(&self, token: &str) -> Option<HandShape> {
+        self.my_shapes.get(token).copied()
+    }
+
+    // The token encoding my shape, the first one in order if there are several
+    pub fn my_token(&self, shape: HandShape) -> Option<&str> {
+        self.my_shapes
+            .iter()
+            .filter(|(_, s)| **s == shape)
+            .map(|(token, _)| token.as_str())
+            .min()
+    }
+
+    pub fn result(&self, token: &str) -> Option<ExpectedResult> {
+        self.results.get(token).copied()
+    }
+}
+
+// Splits a line of the strategy guide into the elf's and my token
+pub fn tokenize(line: &str) -> Option<(&str, &str)> {
+    let mut tokens = line.split_whitespace();
+    let elf_token = tokens.next()?;
+    let my_token = tokens.next()?;
+
+    if tokens.next().is_some() {
+        return None;
+    }
+    Some((elf_token, my_token))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_standard() {
+        let encoding = Encoding::standard();
+        assert_eq!(encoding.elf_shape("B"), Some(HandShape::Paper));
+        assert_eq!(encoding.my_shape("Z"), Some(HandShape::Scissors));
+        assert_eq!(encoding.result("Y"), Some(ExpectedResult::Draw));
+        assert_eq!(encoding.elf_shape("X"), None);
+        assert_eq!(encoding.result("A"), None);
+        assert_eq!(encoding.my_token(HandShape::Paper), Some("Y"));
+    }
+
+    #[test]
+    fn test_parse() {
+        let text = "# words and emojis\nelf rock Rock\n\nme 🪨 rock\nresult ✂️ win\n";
+        let mut encoding = Encoding::parse(text).unwrap();
+        assert_eq!(encoding.elf_shape("rock"), Some(HandShape::Rock));
+        assert_eq!(encoding.my_shape("🪨"), Some(HandShape::Rock));
+        assert_eq!(encoding.result("✂️"), Some(ExpectedResult::Win));
+
+        encoding.add("me 🪨 paper").unwrap();
+        assert_eq!(encoding.my_shape("🪨"), Some(HandShape::Paper));
+
+        assert!(Encoding::parse("elf A").is_err());
+        assert!(Encoding::parse("elf A lizard").is_err());
+        assert!(Encoding::parse("result X maybe").is_err());
+        assert!(Encoding::parse("you A rock").is_err());
+    }
+
+    #[test]
+    fn test_tokenize() {
+        assert_eq!(tokenize("A Y"), Some(("A", "Y")));
+        assert_eq!(tokenize("  rock\t paper "), Some(("rock", "paper")));
+        assert_eq!(tokenize("A"), None);
+        assert_eq!(tokenize("A B C"), None);
+    }
+}
@@ -0,0 +1,252 @@
+// Nash equilibria of two-player matrix games in mixed strategies. The game is
+// not zero-sum: both players collect the points of their own shape, so each
+// player has its own payoff matrix. Equilibria are found by support
+// enumeration: for every pair of equally large sets of rows and columns, the
+// strategies that make the opponent indifferent on its set are computed and
+// kept if no pure strategy outside the sets pays more. This finds an
+// equilibrium of every nondegenerate game and is fast enough for the handful
+// of shapes of the generalized rules.
+
+const EPS: f64 = 1e-9;
+
+#[derive(Debug)]
+pub struct Equilibrium {
+    // expected payoffs of the row and the column player
+    pub row_value: f64,
+    pub col_value: f64,
+    // probabilities of the rows, i.e. of the row player
+    pub row_strategy: Vec<f64>,
+    // probabilities of the columns, i.e. of the column player
+    pub col_strategy: Vec<f64>,
+}
+
+// All sorted selections of k out of n indices
+fn subsets(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+    let mut result = Vec::new();
+    for last in k - 1..n {
+        for mut subset in subsets(last, k - 1) {
+            subset.push(last);
+            result.push(subset);
+        }
+    }
+    result
+}
+
+// Solves the square system a x = b by Gaussian elimination with partial
+// pivoting, None if it is singular
+fn solve_linear(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+    for col in 0..n {
+        let pivot = (col..n).max_by(|i, j| a[*i][col].abs().total_cmp(&a[*j][col].abs()))?;
+        if a[pivot][col].abs() < EPS {
+            return None;
+        }
+        a.swap(col, pivot);
+        b.swap(col, pivot);
+
+        let pivot_row = a[col].clone();
+        for row in col + 1..n {
+            let f = a[row][col] / pivot_row[col];
+            for (v, p) in a[row].iter_mut().zip(&pivot_row).skip(col) {
+                *v -= f * p;
+            }
+            b[row] -= f * b[col];
+        }
+    }
+
+    let mut x = vec![0.0; n];
+    for row in (0..n).rev() {
+        let rest: f64 = (row + 1..n).map(|k| a[row][k] * x[k]).sum();
+        x[row] = (b[row] - rest) / a[row][row];
+    }
+    Some(x)
+}
+
+// The strategy over `support` that makes the opponent indifferent between
+// its `targets`, with the opponent's payoff. payoff(s, t) is the payoff of
+// the opponent playing t against s.
+fn indifferent<F>(
+    support: &[usize],
+    targets: &[usize],
+    size: usize,
+    payoff: F,
+) -> Option<(Vec<f64>, f64)>
+where
+    F: Fn(usize, usize) -> f64,
+{
+    let k = support.len();
+    // unknowns: the probabilities of the support and the payoff
+    let mut a: Vec<Vec<f64>> = targets
+        .iter()
+        .map(|t| {
+            let mut row: Vec<f64> = support.iter().map(|s| payoff(*s, *t)).collect();
+            row.push(-1.0);
+            row
+        })
+        .collect();
+    let mut sum_row = vec![1.0; k];
+    sum_row.push(0.0);
+    a.push(sum_row);
+    let mut b = vec![0.0; k];
+    b.push(1.0);
+
+    let solution = solve_linear(a, b)?;
+    if solution[..k].iter().any(|p| *p < -EPS) {
+        return None;
+    }
+    let mut strategy = vec![0.0; size];
+    for (s, p) in support.iter().zip(&solution) {
+        strategy[*s] = p.max(0.0);
+    }
+    Some((strategy, solution[k]))
+}
+
+// row_payoff[row][col] and col_payoff[row][col] are the payoffs of the row and
+// the column player. Returns the equilibrium with the smallest supports, None
+// only for degenerate games.
+pub fn solve(row_payoff: &[Vec<f64>], col_payoff: &[Vec<f64>]) -> Option<Equilibrium> {
+    let rows = row_payoff.len();
+    let cols = row_payoff[0].len();
+
+    for k in 1..=rows.min(cols) {
+        for row_support in subsets(rows, k) {
+            for col_support in subsets(cols, k) {
+                let Some((col_strategy, row_value)) =
+                    indifferent(&col_support, &row_support, cols, |c, r| row_payoff[r][c])
+                else {
+                    continue;
+                };
+                let Some((row_strategy, col_value)) =
+                    indifferent(&row_support, &col_support, rows, |r, c| col_payoff[r][c])
+                else {
+                    continue;
+                };
+
+                // no pure strategy pays more than the equilibrium
+                let row_best = (0..rows).all(|r| {
+                    expected_payoff(row_payoff, &pure(rows, r), &col_strategy) <= row_value + 1e-6
+                });
+                let col_best = (0..cols).all(|c| {
+                    expected_payoff(col_payoff, &row_strategy, &pure(cols, c)) <= col_value + 1e-6
+                });
+                if row_best && col_best {
+                    return Some(Equilibrium {
+                        row_value,
+                        col_value,
+                        row_strategy,
+                        col_strategy,
+                    });
+                }
+            }
+        }
+    }
+    None
+}
+
+fn pure(size: usize, idx: usize) -> Vec<f64> {
+    let mut strategy = vec![0.0; size];
+    strategy[idx] = 1.0;
+    strategy
+}
+
+// Expected payoff for the given mixed strategies
+pub fn expected_payoff(payoff: &[Vec<f64>], row_strategy: &[f64], col_strategy: &[f64]) -> f64 {
+    payoff
+        .iter()
+        .zip(row_strategy)
+        .map(|(row, p)| {
+            p * row
+                .iter()
+                .zip(col_strategy)
+                .map(|(v, q)| v * q)
+                .sum::<f64>()
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn assert_close(a: f64, b: f64) {
+        assert!((a - b).abs() < 1e-6, "{} != {}", a, b);
+    }
+
+    fn negated(payoff: &[Vec<f64>]) -> Vec<Vec<f64>> {
+        payoff
+            .iter()
+            .map(|row| row.iter().map(|v| -v).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_subsets() {
+        assert_eq!(subsets(3, 2), vec![vec![0, 1], vec![0, 2], vec![1, 2]]);
+        assert_eq!(subsets(4, 4).len(), 1);
+        assert_eq!(subsets(5, 2).len(), 10);
+    }
+
+    #[test]
+    fn test_matching_pennies() {
+        let payoff = vec![vec![1.0, -1.0], vec![-1.0, 1.0]];
+        let eq = solve(&payoff, &negated(&payoff)).unwrap();
+        assert_close(eq.row_value, 0.0);
+        assert_close(eq.row_strategy[0], 0.5);
+        assert_close(eq.col_strategy[1], 0.5);
+    }
+
+    #[test]
+    fn test_saddle_point() {
+        // the row player always picks the second row, the column player the first column
+        let payoff = vec![vec![1.0, 5.0], vec![3.0, 4.0]];
+        let eq = solve(&payoff, &negated(&payoff)).unwrap();
+        assert_close(eq.row_value, 3.0);
+        assert_close(eq.row_strategy[1], 1.0);
+        assert_close(eq.col_strategy[0], 1.0);
+    }
+
+    #[test]
+    fn test_value_is_guaranteed() {
+        let payoff = vec![
+            vec![4.0, 1.0, 7.0],
+            vec![8.0, 5.0, 2.0],
+            vec![3.0, 9.0, 6.0],
+        ];
+        let eq = solve(&payoff, &negated(&payoff)).unwrap();
+        assert_close(eq.row_strategy.iter().sum(), 1.0);
+        assert_close(eq.col_strategy.iter().sum(), 1.0);
+        assert_close(eq.row_value, -eq.col_value);
+
+        // no pure strategy of either player improves on the value
+        for pure in 0..3 {
+            let mut strategy = vec![0.0; 3];
+            strategy[pure] = 1.0;
+            assert!(expected_payoff(&payoff, &strategy, &eq.col_strategy) <= eq.row_value + 1e-6);
+            assert!(expected_payoff(&payoff, &eq.row_strategy, &strategy) >= eq.row_value - 1e-6);
+        }
+    }
+
+    #[test]
+    fn test_general_sum() {
+        // battle of the sexes: the smallest supports are the pure equilibria
+        let row_payoff = vec![vec![3.0, 0.0], vec![0.0, 2.0]];
+        let col_payoff = vec![vec![2.0, 0.0], vec![0.0, 3.0]];
+        let eq = solve(&row_payoff, &col_payoff).unwrap();
+        assert_eq!(eq.row_strategy, vec![1.0, 0.0]);
+        assert_eq!(eq.col_strategy, vec![1.0, 0.0]);
+        assert_close(eq.row_value, 3.0);
+        assert_close(eq.col_value, 2.0);
+
+        // without pure equilibria both mix
+        let row_payoff = vec![vec![2.0, 0.0], vec![0.0, 1.0]];
+        let col_payoff = vec![vec![0.0, 1.0], vec![3.0, 0.0]];
+        let eq = solve(&row_payoff, &col_payoff).unwrap();
+        assert_close(eq.row_strategy[0], 0.75);
+        assert_close(eq.col_strategy[0], 1.0 / 3.0);
+        assert_close(eq.row_value, 2.0 / 3.0);
+        assert_close(eq.col_value, 0.75);
+    }
+}
@@ -1,4 +1,21 @@
 use aoc::read_lines;
+use std::env;
+use std::fs;
+use std::sync::OnceLock;
+
+mod analysis;
+mod encoding;
+mod equilibrium;
+mod mapping;
+mod rules;
+mod synth;
+mod tournament;
+mod trace;
+
+use encoding::{tokenize, Encoding};
+use mapping::Mapping;
+use rules::{ExpectedResult, GameRules};
+use tournament::Strategy;
 
 #[derive(PartialEq, Debug, Clone, Copy)]
 enum HandShape {
@@ -7,117 +24,539 @@ enum HandShape {
     Scissors = 2,
 }
 
+impl HandShape {
+    fn build(name: &str) -> Option<HandShape> {
+        match name.to_lowercase().as_str() {
+            "rock" => Some(HandShape::Rock),
+            "paper" => Some(HandShape::Paper),
+            "scissors" => Some(HandShape::Scissors),
+            _ => None,
+        }
+    }
+
+    fn from_idx(idx: usize) -> HandShape {
+        match idx {
+            0 => HandShape::Rock,
+            1 => HandShape::Paper,
+            2 => HandShape::Scissors,
+            _ => panic!("Unexpected shape index!"),
+        }
+    }
+}
+
 fn shape_idx(shape: HandShape) -> usize {
     shape as usize
 }
 
-fn score(shape: HandShape) -> i32 {
-    shape_idx(shape) as i32 + 1
+// The rules of the game played by the elves
+fn rock_paper_scissors() -> &'static GameRules {
+    static RULES: OnceLock<GameRules> = OnceLock::new();
+    RULES.get_or_init(GameRules::rock_paper_scissors)
 }
 
 fn score_round(elf_shape: HandShape, my_shape: HandShape) -> i32 {
-    let my_score: i32 = score(my_shape);
-    let my_idx: usize = shape_idx(my_shape);
-    let elf_idx: usize = shape_idx(elf_shape);
+    rock_paper_scissors().score_round(shape_idx(elf_shape), shape_idx(my_shape))
+}
+
+fn determine_my_shape(elf_shape: HandShape, result: ExpectedResult) -> HandShape {
+    let my_shape = rock_paper_scissors()
+        .shape_for(shape_idx(elf_shape), result)
+        .expect("Every result is possible in Rock-Paper-Scissors!");
+    HandShape::from_idx(my_shape)
+}
 
-    // win/draw/lose scores for all combinatios of elf and my shapes
-    // row: elf
-    // col: my shape
-    let scores = [3, 6, 0, 0, 3, 6, 6, 0, 3];
+fn score_part1(encoding: &Encoding, elf_token: &str, my_token: &str) -> i32 {
+    let elf_shape = encoding.elf_shape(elf_token).expect("Unknown elf token!");
+    let my_shape = encoding.my_shape(my_token).expect("Unknown shape token!");
 
-    my_score + scores[elf_idx * 3 + my_idx]
+    score_round(elf_shape, my_shape)
 }
 
-fn new_shape(c: char) -> Option<HandShape> {
-    match c {
-        'A' | 'X' => Some(HandShape::Rock),
-        'B' | 'Y' => Some(HandShape::Paper),
-        'C' | 'Z' => Some(HandShape::Scissors),
-        _ => None,
-    }
+fn score_part2(encoding: &Encoding, elf_token: &str, my_token: &str) -> i32 {
+    let elf_shape = encoding.elf_shape(elf_token).expect("Unknown elf token!");
+
+    let result = encoding.result(my_token).expect("Unknown result token!");
+    let my_shape = determine_my_shape(elf_shape, result);
+
+    score_round(elf_shape, my_shape)
 }
 
-fn parse_chars(line: String) -> (char, char) {
-    // No error handling, expect char - whitespace - char in each line!
-    let mut chars = line.chars();
+// The elf's and my token of every round
+fn read_guide(file: &str) -> Vec<(String, String)> {
+    let lines = read_lines(file).unwrap();
+    lines
+        .map_while(Result::ok)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            let (elf_token, my_token) = tokenize(&line).expect("Expected two tokens per line!");
+            (elf_token.to_string(), my_token.to_string())
+        })
+        .collect()
+}
+
+fn solve<F>(file: &str, encoding: &Encoding, score_fn: F) -> i32
+where
+    F: Fn(&Encoding, &str, &str) -> i32,
+{
+    read_guide(file)
+        .iter()
+        .map(|(elf_token, my_token)| score_fn(encoding, elf_token, my_token))
+        .sum()
+}
 
-    let elf_char = chars.next().expect("Expected A/B/C char!");
+// The distinct tokens of my column, sorted
+fn my_tokens(guide: &[(String, String)]) -> Vec<String> {
+    let mut tokens: Vec<String> = guide.iter().map(|(_, token)| token.clone()).collect();
+    tokens.sort();
+    tokens.dedup();
+    tokens
+}
 
-    chars.next();
+fn decode_elf_shapes(guide: &[(String, String)], encoding: &Encoding) -> Vec<(HandShape, String)> {
+    guide
+        .iter()
+        .map(|(elf_token, my_token)| {
+            let elf_shape = encoding.elf_shape(elf_token).expect("Unknown elf token!");
+            (elf_shape, my_token.clone())
+        })
+        .collect()
+}
 
-    let my_char = chars.next().expect("Expected X/Y/Z char!");
-    (elf_char, my_char)
+// Scores of the guide if my column encodes shapes, for every mapping. Fails if
+// my column has more distinct tokens than there are shapes.
+fn shape_mappings(
+    guide: &[(String, String)],
+    encoding: &Encoding,
+) -> Result<Vec<Mapping<HandShape>>, String> {
+    let rounds = decode_elf_shapes(guide, encoding);
+    let shapes = [HandShape::Rock, HandShape::Paper, HandShape::Scissors];
+
+    mapping::search(&my_tokens(guide), &shapes, |map| {
+        rounds
+            .iter()
+            .map(|(elf_shape, token)| score_round(*elf_shape, map[token.as_str()]))
+            .sum()
+    })
 }
 
-enum ExpectedResult {
-    Lose = 0,
-    Draw = 1,
-    Win = 2,
+// Scores of the guide if my column encodes results, for every mapping. Fails
+// if my column has more distinct tokens than there are results.
+fn result_mappings(
+    guide: &[(String, String)],
+    encoding: &Encoding,
+) -> Result<Vec<Mapping<ExpectedResult>>, String> {
+    let rounds = decode_elf_shapes(guide, encoding);
+    let results = [
+        ExpectedResult::Lose,
+        ExpectedResult::Draw,
+        ExpectedResult::Win,
+    ];
+
+    mapping::search(&my_tokens(guide), &results, |map| {
+        rounds
+            .iter()
+            .map(|(elf_shape, token)| {
+                let my_shape = determine_my_shape(*elf_shape, map[token.as_str()]);
+                score_round(*elf_shape, my_shape)
+            })
+            .sum()
+    })
 }
 
-fn expected_result(c: char) -> Option<ExpectedResult> {
-    match c {
-        'X' => Some(ExpectedResult::Lose),
-        'Y' => Some(ExpectedResult::Draw),
-        'Z' => Some(ExpectedResult::Win),
-        _ => None,
+fn print_mappings<T, F>(title: &str, mappings: &[Mapping<T>], name_fn: F)
+where
+    F: Fn(&T) -> String,
+{
+    let describe = |m: &Mapping<T>| -> String {
+        let parts: Vec<String> = m
+            .assignment
+            .iter()
+            .map(|(token, value)| format!("{}={}", token, name_fn(value)))
+            .collect();
+        parts.join(" ")
+    };
+
+    println!("{}", title);
+    for m in mappings {
+        println!("{:>8} {}", m.score, describe(m));
+    }
+    if let Some(best) = mapping::best(mappings) {
+        println!("Best: {} with {}", describe(best), best.score);
+    }
+    if let Some(worst) = mapping::worst(mappings) {
+        println!("Worst: {} with {}", describe(worst), worst.score);
     }
 }
 
-fn determine_my_shape(elf_shape: HandShape, result: ExpectedResult) -> HandShape {
-    let result_idx: usize = result as usize;
-    let elf_idx: usize = shape_idx(elf_shape);
-
-    // row: elf
-    // col: expected result
-    let my_shapes = [
-        HandShape::Scissors,
-        HandShape::Rock,
-        HandShape::Paper,
-        HandShape::Rock,
-        HandShape::Paper,
-        HandShape::Scissors,
-        HandShape::Paper,
-        HandShape::Scissors,
-        HandShape::Rock,
+fn print_tournament(guide: &[(String, String)], encoding: &Encoding, rounds: usize, seed: u64) {
+    let guide_shapes: Vec<HandShape> = guide
+        .iter()
+        .map(|(_, my_token)| encoding.my_shape(my_token).expect("Unknown shape token!"))
+        .collect();
+
+    let bots: Vec<Box<dyn Strategy>> = vec![
+        Box::new(tournament::Constant(HandShape::Rock)),
+        Box::new(tournament::Constant(HandShape::Paper)),
+        Box::new(tournament::Constant(HandShape::Scissors)),
+        Box::new(tournament::Cycling),
+        Box::new(tournament::FrequencyCounter),
+        Box::new(tournament::WinStayLoseShift),
+        Box::new(tournament::ReplayGuide(guide_shapes)),
+        Box::new(tournament::Random),
     ];
 
-    my_shapes[elf_idx * 3 + result_idx]
+    let standings = tournament::round_robin(&bots, rounds, seed);
+
+    println!(
+        "{:>3} {:<22} {:>4} {:>4} {:>4} {:>8}",
+        "#", "Bot", "W", "D", "L", "Points"
+    );
+    for (rank, standing) in standings.iter().enumerate() {
+        println!(
+            "{:>3} {:<22} {:>4} {:>4} {:>4} {:>8}",
+            rank + 1,
+            standing.name,
+            standing.wins,
+            standing.draws,
+            standing.losses,
+            standing.points
+        );
+    }
 }
 
-fn score_part1(elf_char: char, my_char: char) -> i32 {
-    let elf_shape = new_shape(elf_char).unwrap();
-    let my_shape = new_shape(my_char).unwrap();
+// payoff[me][elf] is my score in a round
+fn payoff_matrix(rules: &GameRules) -> Vec<Vec<f64>> {
+    let n = rules.num_shapes();
+    (0..n)
+        .map(|me| {
+            (0..n)
+                .map(|elf| rules.score_round(elf, me) as f64)
+                .collect()
+        })
+        .collect()
+}
 
-    score_round(elf_shape, my_shape)
+// Expected total score of playing the given shapes against an elf playing the
+// mixed strategy
+fn expected_guide_score(my_shapes: &[HandShape], elf_strategy: &[f64]) -> f64 {
+    let payoff = payoff_matrix(rock_paper_scissors());
+    my_shapes
+        .iter()
+        .map(|shape| {
+            let mut mine = vec![0.0; payoff.len()];
+            mine[shape_idx(*shape)] = 1.0;
+            equilibrium::expected_payoff(&payoff, &mine, elf_strategy)
+        })
+        .sum()
 }
 
-fn score_part2(elf_char: char, my_char: char) -> i32 {
-    let elf_shape = new_shape(elf_char).unwrap();
+// My shapes of every round in part one and part two
+fn guide_shapes(
+    guide: &[(String, String)],
+    encoding: &Encoding,
+) -> (Vec<HandShape>, Vec<HandShape>) {
+    guide
+        .iter()
+        .map(|(elf_token, my_token)| {
+            let elf_shape = encoding.elf_shape(elf_token).expect("Unknown elf token!");
+            let my_shape = encoding.my_shape(my_token).expect("Unknown shape token!");
+            let result = encoding.result(my_token).expect("Unknown result token!");
+            (my_shape, determine_my_shape(elf_shape, result))
+        })
+        .unzip()
+}
 
-    let result = expected_result(my_char).unwrap();
-    let my_shape = determine_my_shape(elf_shape, result);
+// The elf's payoff is mine with the roles swapped, elf_payoff[me][elf] is the
+// elf's score in a round
+fn elf_payoff_matrix(rules: &GameRules) -> Vec<Vec<f64>> {
+    let n = rules.num_shapes();
+    (0..n)
+        .map(|me| {
+            (0..n)
+                .map(|elf| rules.score_round(me, elf) as f64)
+                .collect()
+        })
+        .collect()
+}
 
-    score_round(elf_shape, my_shape)
+fn game_equilibrium(rules: &GameRules) -> equilibrium::Equilibrium {
+    equilibrium::solve(&payoff_matrix(rules), &elf_payoff_matrix(rules))
+        .expect("Expected a nondegenerate game!")
 }
 
-fn solve<F: Fn(char, char) -> i32>(file: &str, score_fn: F) -> i32 {
-    let mut total_score = 0;
+fn print_equilibrium(rules: &GameRules) -> equilibrium::Equilibrium {
+    let eq = game_equilibrium(rules);
+
+    println!(
+        "Expected points per round: {:.4} for me, {:.4} for the elf",
+        eq.row_value, eq.col_value
+    );
+    println!("{:>10} {:>8} {:>8}", "Shape", "Me", "Elf");
+    for shape in 0..rules.num_shapes() {
+        println!(
+            "{:>10} {:>8.4} {:>8.4}",
+            rules.name(shape),
+            eq.row_strategy[shape],
+            eq.col_strategy[shape]
+        );
+    }
+    eq
+}
 
-    let lines = read_lines(file).unwrap();
-    for rline in lines {
-        if let Ok(line) = rline {
-            let (elf_char, my_char) = parse_chars(line);
+fn print_analysis(guide: &[(String, String)], encoding: &Encoding, output: Option<&String>) {
+    let shapes = [HandShape::Rock, HandShape::Paper, HandShape::Scissors];
+    let (elf_moves, my_moves): (Vec<HandShape>, Vec<HandShape>) =
+        decode_elf_shapes(guide, encoding)
+            .iter()
+            .map(|(elf_shape, my_token)| {
+                let my_shape = encoding.my_shape(my_token).expect("Unknown shape token!");
+                (*elf_shape, my_shape)
+            })
+            .unzip();
+
+    println!("Shape frequencies of the elf");
+    for (shape, p) in shapes.iter().zip(analysis::frequencies(&elf_moves)) {
+        println!("{:>10} {:>8.4}", format!("{:?}", shape), p);
+    }
 
-            total_score += score_fn(elf_char, my_char);
+    println!("Transition probabilities (row: previous, col: next)");
+    for (shape, row) in shapes.iter().zip(analysis::transitions(&elf_moves)) {
+        let probabilities: Vec<String> = row.iter().map(|p| format!("{:>8.4}", p)).collect();
+        println!("{:>10} {}", format!("{:?}", shape), probabilities.join(" "));
+    }
+
+    let predictions = analysis::predictions(&elf_moves);
+    let best_moves: Vec<HandShape> = predictions.iter().map(analysis::best_response).collect();
+
+    let expected = |moves: &[HandShape]| -> f64 {
+        moves
+            .iter()
+            .zip(&predictions)
+            .map(|(shape, prediction)| analysis::expected_score(*shape, prediction))
+            .sum()
+    };
+    let actual = |moves: &[HandShape]| -> i32 {
+        moves
+            .iter()
+            .zip(&elf_moves)
+            .map(|(my_shape, elf_shape)| score_round(*elf_shape, *my_shape))
+            .sum()
+    };
+
+    println!(
+        "Provided guide: expected score {:.1}, actual score {}",
+        expected(&my_moves),
+        actual(&my_moves)
+    );
+    println!(
+        "Best response:  expected score {:.1}, actual score {}",
+        expected(&best_moves),
+        actual(&best_moves)
+    );
+
+    if let Some(file) = output {
+        let lines: Vec<String> = guide
+            .iter()
+            .zip(&best_moves)
+            .map(|((elf_token, _), shape)| {
+                let token = encoding.my_token(*shape).expect("Shape without token!");
+                format!("{} {}\n", elf_token, token)
+            })
+            .collect();
+        fs::write(file, lines.concat()).expect("Unable to write file");
+        println!("Wrote the best response guide to {}", file);
+    }
+}
+
+fn select_rules(args: &[String]) -> GameRules {
+    match args.first().map(String::as_str) {
+        None | Some("rps") => GameRules::rock_paper_scissors(),
+        Some("rpsls") => GameRules::rock_paper_scissors_lizard_spock(),
+        Some(_) => {
+            let names: Vec<&str> = args.iter().map(String::as_str).collect();
+            GameRules::cyclic(&names).unwrap()
         }
     }
-    total_score
+}
+
+// Removes the --encoding <file>, --map '<column> <token> <meaning>' and
+// --guide <file> options from the arguments
+fn parse_options(args: &[String]) -> (Encoding, String, Vec<String>) {
+    let mut encoding = Encoding::standard();
+    let mut guide = String::from("data/day2/input");
+    let mut positional = Vec::new();
+
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--encoding" => {
+                let file = it.next().expect("Expected encoding file!");
+                encoding = Encoding::load(file).unwrap();
+            }
+            "--map" => {
+                let line = it.next().expect("Expected encoding entry!");
+                encoding.add(line).unwrap();
+            }
+            "--guide" => {
+                guide = it.next().expect("Expected strategy guide file!").clone();
+            }
+            _ => positional.push(arg.clone()),
+        }
+    }
+    (encoding, guide, positional)
+}
+
+fn print_rules(rules: &GameRules) {
+    let n = rules.num_shapes();
+
+    println!("Outcomes (row: elf, col: my shape)");
+    for (elf, outcomes) in rules.outcome_table().iter().enumerate() {
+        let row: Vec<String> = outcomes
+            .iter()
+            .map(|outcome| format!("{:>4}", outcome.name()))
+            .collect();
+        println!("{:>10} {}", rules.name(elf), row.join(" "));
+    }
+
+    println!("Shapes to play (row: elf, col: lose/draw/win)");
+    for elf in 0..n {
+        let row: Vec<String> = [
+            ExpectedResult::Lose,
+            ExpectedResult::Draw,
+            ExpectedResult::Win,
+        ]
+        .iter()
+        .map(|result| {
+            let shapes: Vec<&str> = rules
+                .shapes_for(elf, *result)
+                .iter()
+                .map(|shape| rules.name(*shape))
+                .collect();
+            format!("{:>16}", shapes.join("/"))
+        })
+        .collect();
+        println!("{:>10} {}", rules.name(elf), row.join(" "));
+    }
 }
 
 fn main() {
-    let score_pt1 = solve("data/day2/input", score_part1);
-    let score_pt2 = solve("data/day2/input", score_part2);
+    let (encoding, guide, args) = parse_options(&env::args().collect::<Vec<String>>());
+
+    if args.len() > 1 && args[1] == "rules" {
+        // day2 rules [rps|rpsls|<shape> <shape> ...]
+        let rules = select_rules(&args[2..]);
+        print_rules(&rules);
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "equilibrium" {
+        // day2 equilibrium [rps|rpsls|<shape> <shape> ...]
+        let rules = select_rules(&args[2..]);
+        let eq = print_equilibrium(&rules);
+
+        if rules.num_shapes() == 3 && args.len() <= 3 {
+            let (shapes_pt1, shapes_pt2) = guide_shapes(&read_guide(&guide), &encoding);
+            println!(
+                "Expected score of the guide against the equilibrium elf in Pt. 1 is {:.1}",
+                expected_guide_score(&shapes_pt1, &eq.col_strategy)
+            );
+            println!(
+                "Expected score of the guide against the equilibrium elf in Pt. 2 is {:.1}",
+                expected_guide_score(&shapes_pt2, &eq.col_strategy)
+            );
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "analyze" {
+        // day2 analyze [output file for the best response guide]
+        print_analysis(&read_guide(&guide), &encoding, args.get(2));
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "trace" {
+        // day2 trace [1|2] [csv]
+        let part = match args.get(2).map(String::as_str) {
+            None | Some("1") => trace::Part::One,
+            Some("2") => trace::Part::Two,
+            Some(_) => panic!("Expected part 1 or 2!"),
+        };
+        let rows = trace::trace(&read_guide(&guide), &encoding, part);
+
+        if args.get(3).map(String::as_str) == Some("csv") {
+            print!("{}", trace::to_csv(&rows));
+        } else {
+            print!("{}", trace::to_table(&rows));
+        }
+        return;
+    }
+
+    if args.len() > 3 && args[1] == "synth" {
+        // day2 synth <rounds> <total pt. 1 or -> <total pt. 2 or -> [output file]
+        let rounds = args[2]
+            .parse::<usize>()
+            .expect("Expected number of rounds!");
+        let target = |arg: &str| match arg {
+            "-" => None,
+            _ => Some(arg.parse::<i32>().expect("Expected total score or -!")),
+        };
+
+        match synth::synthesize(
+            rounds,
+            target(&args[3]),
+            args.get(4).and_then(|a| target(a)),
+        ) {
+            Ok(rounds) => {
+                let text: String = rounds
+                    .iter()
+                    .map(|(elf_token, my_token)| format!("{} {}\n", elf_token, my_token))
+                    .collect();
+                match args.get(5) {
+                    Some(file) => fs::write(file, text).expect("Unable to write file"),
+                    None => print!("{}", text),
+                }
+            }
+            Err(e) => println!("{}", e),
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "mappings" {
+        let guide_rounds = read_guide(&guide);
+
+        match shape_mappings(&guide_rounds, &encoding) {
+            Ok(shapes) => print_mappings("Second column as shapes", &shapes, |shape| {
+                format!("{:?}", shape)
+            }),
+            Err(e) => println!("Second column as shapes: {}", e),
+        }
+
+        match result_mappings(&guide_rounds, &encoding) {
+            Ok(results) => print_mappings("Second column as results", &results, |result| {
+                result.name().to_string()
+            }),
+            Err(e) => println!("Second column as results: {}", e),
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "tournament" {
+        // day2 tournament [rounds] [seed]
+        let rounds = args
+            .get(2)
+            .map(|s| s.parse::<usize>().expect("Expected number of rounds!"))
+            .unwrap_or(1000);
+        let seed = args
+            .get(3)
+            .map(|s| s.parse::<u64>().expect("Expected seed!"))
+            .unwrap_or(2022);
+
+        print_tournament(&read_guide(&guide), &encoding, rounds, seed);
+        return;
+    }
+
+    let score_pt1 = solve(&guide, &encoding, score_part1);
+    let score_pt2 = solve(&guide, &encoding, score_part2);
 
     println!("Total score in Pt. 1 is {}", score_pt1);
     println!("Total score in Pt. 2 is {}", score_pt2);
@@ -192,7 +631,148 @@ mod tests {
 
     #[test]
     fn test_after_completion() {
-        assert_eq!(14069, solve("data/day2/input", score_part1));
-        assert_eq!(12411, solve("data/day2/input", score_part2));
+        let encoding = Encoding::standard();
+        assert_eq!(14069, solve("data/day2/input", &encoding, score_part1));
+        assert_eq!(12411, solve("data/day2/input", &encoding, score_part2));
+    }
+
+    #[test]
+    fn test_mappings() {
+        let encoding = Encoding::standard();
+        let guide = read_guide("data/day2/input");
+
+        let shapes = shape_mappings(&guide, &encoding).unwrap();
+        assert_eq!(shapes.len(), 6);
+        let puzzle_mapping = vec![
+            (String::from("X"), HandShape::Rock),
+            (String::from("Y"), HandShape::Paper),
+            (String::from("Z"), HandShape::Scissors),
+        ];
+        let puzzle = shapes
+            .iter()
+            .find(|m| m.assignment == puzzle_mapping)
+            .unwrap();
+        assert_eq!(puzzle.score, 14069);
+        assert!(mapping::best(&shapes).unwrap().score >= 14069);
+        assert!(mapping::worst(&shapes).unwrap().score <= 14069);
+
+        let results = result_mappings(&guide, &encoding).unwrap();
+        assert_eq!(results.len(), 6);
+        let puzzle = results
+            .iter()
+            .find(|m| {
+                m.assignment[0].1 == ExpectedResult::Lose
+                    && m.assignment[2].1 == ExpectedResult::Win
+            })
+            .unwrap();
+        assert_eq!(puzzle.score, 12411);
+    }
+
+    #[test]
+    fn test_mappings_too_many_tokens() {
+        // several tokens for the same shape are valid, but can't be mapped
+        // one-to-one
+        let mut encoding = Encoding::standard();
+        encoding.add("me W rock").unwrap();
+        let guide: Vec<(String, String)> = ["A X", "B Y", "C Z", "A W"]
+            .iter()
+            .map(|line| {
+                let (elf, me) = tokenize(line).unwrap();
+                (elf.to_string(), me.to_string())
+            })
+            .collect();
+        let score: i32 = guide
+            .iter()
+            .map(|(elf, me)| score_part1(&encoding, elf, me))
+            .sum();
+        assert_eq!(score, 4 + 5 + 6 + 4);
+
+        let err = "Can't map 4 tokens to 3 values one-to-one";
+        assert_eq!(
+            shape_mappings(&guide, &encoding).err().as_deref(),
+            Some(err)
+        );
+        assert_eq!(
+            result_mappings(&guide, &encoding).err().as_deref(),
+            Some(err)
+        );
+    }
+
+    #[test]
+    fn test_equilibrium() {
+        let eq = game_equilibrium(rock_paper_scissors());
+        let sum: f64 = eq.col_strategy.iter().sum();
+        assert!((sum - 1.0).abs() < 1e-6);
+
+        // the symmetric equilibrium, both play rock and scissors more often
+        // since they are worth one point less and one point more than paper
+        for strategy in [&eq.row_strategy, &eq.col_strategy] {
+            for (p, expected) in strategy.iter().zip([4.0 / 9.0, 1.0 / 9.0, 4.0 / 9.0]) {
+                assert!((p - expected).abs() < 1e-6);
+            }
+        }
+        assert!((eq.row_value - 5.0).abs() < 1e-6);
+        assert!((eq.col_value - 5.0).abs() < 1e-6);
+        let payoff = payoff_matrix(rock_paper_scissors());
+        let value = equilibrium::expected_payoff(&payoff, &eq.row_strategy, &eq.col_strategy);
+        assert!((value - eq.row_value).abs() < 1e-6);
+
+        // against the equilibrium elf every shape I play yields at most the value
+        for shape in [HandShape::Rock, HandShape::Paper, HandShape::Scissors] {
+            assert!(expected_guide_score(&[shape], &eq.col_strategy) <= eq.row_value + 1e-6);
+        }
+
+        let (shapes_pt1, shapes_pt2) = guide_shapes(
+            &read_guide("data/day2/test_words"),
+            &Encoding::load("data/day2/words.encoding").unwrap(),
+        );
+        assert_eq!(
+            shapes_pt1,
+            vec![HandShape::Paper, HandShape::Rock, HandShape::Scissors]
+        );
+        assert_eq!(
+            shapes_pt2,
+            vec![HandShape::Rock, HandShape::Rock, HandShape::Rock]
+        );
+    }
+
+    #[test]
+    fn test_trace_total() {
+        let encoding = Encoding::standard();
+        let guide = read_guide("data/day2/input");
+
+        let rows = trace::trace(&guide, &encoding, trace::Part::One);
+        assert_eq!(rows.last().unwrap().total, 14069);
+        let rows = trace::trace(&guide, &encoding, trace::Part::Two);
+        assert_eq!(rows.last().unwrap().total, 12411);
+    }
+
+    #[test]
+    fn test_synth_round_trip() {
+        let file = env::temp_dir().join("aoc_day2_synth_test");
+        let file = file.to_str().unwrap();
+        let encoding = Encoding::standard();
+
+        for (rounds, pt1, pt2) in [(2500, 14069, 12411), (100, 500, 500), (10, 50, 60)] {
+            let guide = synth::synthesize(rounds, Some(pt1), Some(pt2)).unwrap();
+            let text: String = guide
+                .iter()
+                .map(|(elf_token, my_token)| format!("{} {}\n", elf_token, my_token))
+                .collect();
+            fs::write(file, text).unwrap();
+
+            assert_eq!(read_guide(file).len(), rounds);
+            assert_eq!(solve(file, &encoding, score_part1), pt1);
+            assert_eq!(solve(file, &encoding, score_part2), pt2);
+        }
+        fs::remove_file(file).unwrap();
+    }
+
+    #[test]
+    fn test_custom_encoding() {
+        // example from the task description, written with words
+        let encoding = Encoding::load("data/day2/words.encoding").unwrap();
+        assert_eq!(15, solve("data/day2/test_words", &encoding, score_part1));
+        assert_eq!(12, solve("data/day2/test_words", &encoding, score_part2));
     }
 }
@@ -0,0 +1,105 @@
+// Searches all interpretations of the second column of the strategy guide,
+// i.e. every one-to-one mapping of its tokens to shapes or results.
+
+use std::collections::HashMap;
+
+pub struct Mapping<T> {
+    pub assignment: Vec<(String, T)>,
+    pub score: i32,
+}
+
+// All ordered selections of k out of n indices
+fn permutations(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![Vec::new()];
+    }
+
+    let mut result = Vec::new();
+    for prefix in permutations(n, k - 1) {
+        for idx in 0..n {
+            if !prefix.contains(&idx) {
+                let mut perm = prefix.clone();
+                perm.push(idx);
+                result.push(perm);
+            }
+        }
+    }
+    result
+}
+
+// Scores the guide under every mapping of the (distinct) tokens to distinct
+// values. There must not be more tokens than values.
+pub fn search<T, F>(tokens: &[String], values: &[T], score_fn: F) -> Result<Vec<Mapping<T>>, String>
+where
+    T: Copy,
+    F: Fn(&HashMap<&str, T>) -> i32,
+{
+    if tokens.len() > values.len() {
+        return Err(format!(
+            "Can't map {} tokens to {} values one-to-one",
+            tokens.len(),
+            values.len()
+        ));
+    }
+
+    let mappings = permutations(values.len(), tokens.len())
+        .iter()
+        .map(|perm| {
+            let map: HashMap<&str, T> = tokens
+                .iter()
+                .zip(perm)
+                .map(|(token, idx)| (token.as_str(), values[*idx]))
+                .collect();
+            let assignment = tokens
+                .iter()
+                .zip(perm)
+                .map(|(token, idx)| (token.clone(), values[*idx]))
+                .collect();
+
+            Mapping {
+                assignment,
+                score: score_fn(&map),
+            }
+        })
+        .collect();
+    Ok(mappings)
+}
+
+pub fn best<T>(mappings: &[Mapping<T>]) -> Option<&Mapping<T>> {
+    mappings.iter().max_by_key(|m| m.score)
+}
+
+pub fn worst<T>(mappings: &[Mapping<T>]) -> Option<&Mapping<T>> {
+    mappings.iter().min_by_key(|m| m.score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permutations() {
+        assert_eq!(permutations(3, 3).len(), 6);
+        assert_eq!(permutations(3, 2).len(), 6);
+        assert_eq!(permutations(3, 1), vec![vec![0], vec![1], vec![2]]);
+        assert_eq!(permutations(2, 0), vec![Vec::<usize>::new()]);
+    }
+
+    #[test]
+    fn test_search() {
+        let tokens = vec![String::from("X"), String::from("Y")];
+        let mappings = search(&tokens, &[1, 10, 100], |map| map["X"] - map["Y"]).unwrap();
+        assert_eq!(mappings.len(), 6);
+
+        let best = best(&mappings).unwrap();
+        assert_eq!(best.score, 99);
+        assert_eq!(
+            best.assignment,
+            vec![(String::from("X"), 100), (String::from("Y"), 1)]
+        );
+        assert_eq!(worst(&mappings).unwrap().score, -99);
+
+        let too_many = vec![String::from("X"), String::from("Y"), String::from("Z")];
+        assert!(search(&too_many, &[1, 2], |_| 0).is_err());
+    }
+}
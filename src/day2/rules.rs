@@ -0,0 +1,219 @@
+// Rules of cyclic hand games like Rock-Paper-Scissors: with an odd number N of
+// shapes every shape beats (N-1)/2 others and loses against the rest.
+
+#[derive(PartialEq, Debug, Clone, Copy)]
+pub enum ExpectedResult {
+    Lose = 0,
+    Draw = 1,
+    Win = 2,
+}
+
+impl ExpectedResult {
+    pub fn score(self) -> i32 {
+        self as i32 * 3
+    }
+
+    pub fn name(self) -> &'static str {
+        match self {
+            ExpectedResult::Lose => "lose",
+            ExpectedResult::Draw => "draw",
+            ExpectedResult::Win => "win",
+        }
+    }
+}
+
+pub struct GameRules {
+    names: Vec<String>,
+    // beats[a * N + b] is true if shape a beats shape b
+    beats: Vec<bool>,
+}
+
+impl GameRules {
+    // Builds the rules from a list of (winner, loser) pairs of shape indices.
+    // Shapes score their index plus one.
+    pub fn build(names: &[&str], wins: &[(usize, usize)]) -> Result<GameRules, String> {
+        let n = names.len();
+        if n.is_multiple_of(2) {
+            return Err(format!("Expected an odd number of shapes, got {}", n));
+        }
+
+        let mut beats = vec![false; n * n];
+        for (winner, loser) in wins {
+            if *winner >= n || *loser >= n {
+                return Err(format!("Unknown shape in {} beats {}", winner, loser));
+            }
+            if winner == loser {
+                return Err(format!("{} can't beat itself", names[*winner]));
+            }
+            if beats[loser * n + winner] {
+                return Err(format!(
+                    "{} and {} beat each other",
+                    names[*winner], names[*loser]
+                ));
+            }
+            beats[winner * n + loser] = true;
+        }
+
+        for (shape, name) in names.iter().enumerate() {
+            let num_beaten = beats[shape * n..(shape + 1) * n]
+                .iter()
+                .filter(|b| **b)
+                .count();
+            if num_beaten != (n - 1) / 2 {
+                return Err(format!(
+                    "{} beats {} shapes instead of {}",
+                    name,
+                    num_beaten,
+                    (n - 1) / 2
+                ));
+            }
+        }
+
+        Ok(GameRules {
+            names: names.iter().map(|name| name.to_string()).collect(),
+            beats,
+        })
+    }
+
+    // Every shape beats the (N-1)/2 shapes preceding it, wrapping around
+    pub fn cyclic(names: &[&str]) -> Result<GameRules, String> {
+        let n = names.len();
+
+        let mut wins = Vec::new();
+        for winner in 0..n {
+            for offset in 1..=n.saturating_sub(1) / 2 {
+                wins.push((winner, (winner + n - offset) % n));
+            }
+        }
+        GameRules::build(names, &wins)
+    }
+
+    pub fn rock_paper_scissors() -> GameRules {
+        GameRules::cyclic(&["Rock", "Paper", "Scissors"]).unwrap()
+    }
+
+    pub fn rock_paper_scissors_lizard_spock() -> GameRules {
+        GameRules::cyclic(&["Rock", "Spock", "Paper", "Lizard", "Scissors"]).unwrap()
+    }
+
+    pub fn num_shapes(&self) -> usize {
+        self.names.len()
+    }
+
+    pub fn name(&self, shape: usize) -> &str {
+        &self.names[shape]
+    }
+
+    pub fn shape_score(&self, shape: usize) -> i32 {
+        shape as i32 + 1
+    }
+
+    pub fn outcome(&self, elf_shape: usize, my_shape: usize) -> ExpectedResult {
+        let n = self.num_shapes();
+        if self.beats[my_shape * n + elf_shape] {
+            ExpectedResult::Win
+        } else if self.beats[elf_shape * n + my_shape] {
+            ExpectedResult::Lose
+        } else {
+            ExpectedResult::Draw
+        }
+    }
+
+    pub fn score_round(&self, elf_shape: usize, my_shape: usize) -> i32 {
+        self.shape_score(my_shape) + self.outcome(elf_shape, my_shape).score()
+    }
+
+    // row: elf
+    // col: my shape
+    pub fn outcome_table(&self) -> Vec<Vec<ExpectedResult>> {
+        let n = self.num_shapes();
+        (0..n)
+            .map(|elf| (0..n).map(|me| self.outcome(elf, me)).collect())
+            .collect()
+    }
+
+    // All of my shapes leading to the result against the elf's shape
+    pub fn shapes_for(&self, elf_shape: usize, result: ExpectedResult) -> Vec<usize> {
+        (0..self.num_shapes())
+            .filter(|me| self.outcome(elf_shape, *me) == result)
+            .collect()
+    }
+
+    // The highest scoring shape leading to the result, if there is any
+    pub fn shape_for(&self, elf_shape: usize, result: ExpectedResult) -> Option<usize> {
+        self.shapes_for(elf_shape, result).last().copied()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rock_paper_scissors() {
+        let rules = GameRules::rock_paper_scissors();
+        assert_eq!(rules.num_shapes(), 3);
+
+        // rock loses against paper, paper against scissors, scissors against rock
+        assert_eq!(rules.outcome(0, 1), ExpectedResult::Win);
+        assert_eq!(rules.outcome(1, 2), ExpectedResult::Win);
+        assert_eq!(rules.outcome(2, 0), ExpectedResult::Win);
+        assert_eq!(rules.outcome(1, 0), ExpectedResult::Lose);
+        assert_eq!(rules.outcome(2, 2), ExpectedResult::Draw);
+
+        assert_eq!(rules.score_round(0, 1), 8);
+        assert_eq!(rules.shape_for(0, ExpectedResult::Lose), Some(2));
+    }
+
+    #[test]
+    fn test_lizard_spock() {
+        let rules = GameRules::rock_paper_scissors_lizard_spock();
+        let rock = 0;
+        let spock = 1;
+        let paper = 2;
+        let lizard = 3;
+        let scissors = 4;
+
+        assert_eq!(rules.outcome(rock, spock), ExpectedResult::Win);
+        assert_eq!(rules.outcome(spock, lizard), ExpectedResult::Win);
+        assert_eq!(rules.outcome(lizard, rock), ExpectedResult::Win);
+        assert_eq!(rules.outcome(paper, scissors), ExpectedResult::Win);
+        assert_eq!(rules.outcome(scissors, spock), ExpectedResult::Win);
+        assert_eq!(rules.outcome(lizard, scissors), ExpectedResult::Win);
+        assert_eq!(rules.outcome(rock, paper), ExpectedResult::Win);
+        assert_eq!(rules.outcome(paper, lizard), ExpectedResult::Win);
+
+        assert_eq!(
+            rules.shapes_for(rock, ExpectedResult::Win),
+            vec![spock, paper]
+        );
+        assert_eq!(rules.shape_for(rock, ExpectedResult::Draw), Some(rock));
+    }
+
+    #[test]
+    fn test_outcome_table() {
+        for rules in [
+            GameRules::rock_paper_scissors(),
+            GameRules::rock_paper_scissors_lizard_spock(),
+            GameRules::cyclic(&["a", "b", "c", "d", "e", "f", "g"]).unwrap(),
+        ] {
+            let n = rules.num_shapes();
+            for (elf, row) in rules.outcome_table().iter().enumerate() {
+                let wins = row.iter().filter(|r| **r == ExpectedResult::Win).count();
+                let losses = row.iter().filter(|r| **r == ExpectedResult::Lose).count();
+                assert_eq!(wins, (n - 1) / 2);
+                assert_eq!(losses, (n - 1) / 2);
+                assert_eq!(row[elf], ExpectedResult::Draw);
+            }
+        }
+    }
+
+    #[test]
+    fn test_invalid_rules() {
+        assert!(GameRules::cyclic(&["a", "b"]).is_err());
+        assert!(GameRules::build(&["a", "b", "c"], &[(0, 1), (1, 0), (2, 0)]).is_err());
+        assert!(GameRules::build(&["a", "b", "c"], &[(0, 1), (1, 2), (0, 2)]).is_err());
+        assert!(GameRules::build(&["a", "b", "c"], &[(0, 0), (1, 2), (2, 0)]).is_err());
+        assert!(GameRules::build(&["a", "b", "c"], &[(1, 0), (2, 1), (0, 2)]).is_ok());
+    }
+}
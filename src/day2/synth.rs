@@ -0,0 +1,259 @@
+// Generates strategy guides (with the A/B/C and X/Y/Z encoding) which score
+// exactly the given totals in part one and/or part two.
+//
+// Every round is one of nine types. Three of them score the same in both
+// parts (1, 5 or 9 points), the others differ by -5, -4, -1, 1, 4 or 5
+// points. Two rounds differing by d and -d together score the same as two
+// rounds with 5 points each, so only one sign per difference is needed and
+// the search over the counts of the differing rounds is exhaustive.
+
+use super::encoding::Encoding;
+use super::{score_part1, score_part2};
+
+const ELF_TOKENS: [&str; 3] = ["A", "B", "C"];
+const MY_TOKENS: [&str; 3] = ["X", "Y", "Z"];
+
+#[derive(Clone, Copy)]
+struct RoundType {
+    elf_token: &'static str,
+    my_token: &'static str,
+    score_pt1: i32,
+    score_pt2: i32,
+}
+
+impl RoundType {
+    fn diff(&self) -> i32 {
+        self.score_pt1 - self.score_pt2
+    }
+}
+
+fn round_types() -> Vec<RoundType> {
+    let encoding = Encoding::standard();
+
+    let mut types = Vec::new();
+    for elf_token in ELF_TOKENS {
+        for my_token in MY_TOKENS {
+            types.push(RoundType {
+                elf_token,
+                my_token,
+                score_pt1: score_part1(&encoding, elf_token, my_token),
+                score_pt2: score_part2(&encoding, elf_token, my_token),
+            });
+        }
+    }
+    types
+}
+
+fn find_type(types: &[RoundType], predicate: impl Fn(&RoundType) -> bool) -> RoundType {
+    *types
+        .iter()
+        .find(|t| predicate(t))
+        .expect("Missing round type!")
+}
+
+// Rounds scoring exactly `total` in one part, using one type per score 1..9
+fn fill_single<F>(
+    types: &[RoundType],
+    rounds: usize,
+    total: i32,
+    score_fn: F,
+) -> Option<Vec<RoundType>>
+where
+    F: Fn(&RoundType) -> i32,
+{
+    let n = rounds as i32;
+    if total < n || total > 9 * n {
+        return None;
+    }
+
+    let mut extra = total - n;
+    let mut result = Vec::new();
+    for _ in 0..rounds {
+        let score = 1 + extra.min(8);
+        extra -= score - 1;
+        result.push(find_type(types, |t| score_fn(t) == score));
+    }
+    Some(result)
+}
+
+// Rounds scoring the same in both parts (1, 5 or 9) adding up to `total`
+fn fill_diagonal(types: &[RoundType], rounds: usize, total: i32) -> Option<Vec<RoundType>> {
+    let n = rounds as i32;
+    if total < n || total > 9 * n || (total - n) % 4 != 0 {
+        return None;
+    }
+
+    // every five adds 4, every nine adds 8 to the minimum of one per round
+    let steps = (total - n) / 4;
+    let nines = steps / 2;
+    let fives = steps % 2;
+    let ones = n - nines - fives;
+
+    let mut result = Vec::new();
+    for (count, score) in [(ones, 1), (fives, 5), (nines, 9)] {
+        let t = find_type(types, |t| t.diff() == 0 && t.score_pt1 == score);
+        result.extend(std::iter::repeat_n(t, count as usize));
+    }
+    Some(result)
+}
+
+fn fill_both(
+    types: &[RoundType],
+    rounds: usize,
+    total_pt1: i32,
+    total_pt2: i32,
+) -> Option<Vec<RoundType>> {
+    let n = rounds as i32;
+    let diff = total_pt1 - total_pt2;
+
+    // the round type for a difference of d and -d points
+    let by_diff = |d: i32, count: i32| find_type(types, |t| t.diff() == d * count.signum());
+    let pairs = [1, 4, 5].map(|d| (by_diff(d, 1), by_diff(d, -1)));
+
+    // count of rounds differing by 1, 4 and 5 points, negative counts select
+    // the rounds differing by -1, -4 and -5
+    for ones in -n..=n {
+        let max_fours = n - ones.abs();
+        for fours in -max_fours..=max_fours {
+            let rest = diff - ones - 4 * fours;
+            if rest % 5 != 0 {
+                continue;
+            }
+            let fives = rest / 5;
+            let used = ones.abs() + fours.abs() + fives.abs();
+            if used > n {
+                continue;
+            }
+
+            let counts = [ones, fours, fives];
+            let chosen = counts
+                .iter()
+                .zip(&pairs)
+                .map(|(count, (positive, negative))| {
+                    let t = if *count > 0 { positive } else { negative };
+                    (*t, count.unsigned_abs() as usize)
+                });
+
+            let scored: i32 = chosen
+                .clone()
+                .map(|(t, count)| t.score_pt1 * count as i32)
+                .sum();
+            if let Some(diagonal) = fill_diagonal(types, (n - used) as usize, total_pt1 - scored) {
+                let mut result: Vec<RoundType> = chosen
+                    .flat_map(|(t, count)| std::iter::repeat_n(t, count))
+                    .collect();
+                result.extend(diagonal);
+                return Some(result);
+            }
+        }
+    }
+    None
+}
+
+// A guide with the given number of rounds scoring exactly the given totals.
+// Returns an error if no such guide exists.
+pub fn synthesize(
+    rounds: usize,
+    total_pt1: Option<i32>,
+    total_pt2: Option<i32>,
+) -> Result<Vec<(String, String)>, String> {
+    let types = round_types();
+
+    let found = match (total_pt1, total_pt2) {
+        (None, None) => Some(vec![types[0]; rounds]),
+        (Some(total), None) => fill_single(&types, rounds, total, |t| t.score_pt1),
+        (None, Some(total)) => fill_single(&types, rounds, total, |t| t.score_pt2),
+        (Some(pt1), Some(pt2)) => fill_both(&types, rounds, pt1, pt2),
+    };
+
+    match found {
+        Some(guide) => Ok(guide
+            .iter()
+            .map(|t| (t.elf_token.to_string(), t.my_token.to_string()))
+            .collect()),
+        None => Err(format!(
+            "There is no guide with {} rounds scoring {} in part one and {} in part two",
+            rounds,
+            total_pt1.map_or(String::from("anything"), |t| t.to_string()),
+            total_pt2.map_or(String::from("anything"), |t| t.to_string()),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn totals(guide: &[(String, String)]) -> (i32, i32) {
+        let encoding = Encoding::standard();
+        guide.iter().fold((0, 0), |(pt1, pt2), (elf, me)| {
+            (
+                pt1 + score_part1(&encoding, elf, me),
+                pt2 + score_part2(&encoding, elf, me),
+            )
+        })
+    }
+
+    #[test]
+    fn test_round_types() {
+        let types = round_types();
+        let mut diffs: Vec<i32> = types.iter().map(|t| t.diff()).collect();
+        diffs.sort();
+        assert_eq!(diffs, vec![-5, -4, -1, 0, 0, 0, 1, 4, 5]);
+    }
+
+    #[test]
+    fn test_single_part() {
+        let guide = synthesize(3, Some(15), None).unwrap();
+        assert_eq!(guide.len(), 3);
+        assert_eq!(totals(&guide).0, 15);
+
+        let guide = synthesize(4, None, Some(35)).unwrap();
+        assert_eq!(totals(&guide).1, 35);
+
+        assert!(synthesize(3, Some(2), None).is_err());
+        assert!(synthesize(3, None, Some(28)).is_err());
+    }
+
+    #[test]
+    fn test_both_parts() {
+        let guide = synthesize(3, Some(15), Some(12)).unwrap();
+        assert_eq!(totals(&guide), (15, 12));
+
+        // exhaustive for small guides
+        for rounds in 0..4 {
+            let n = rounds as i32;
+            for pt1 in n..=9 * n {
+                for pt2 in n..=9 * n {
+                    if let Ok(guide) = synthesize(rounds, Some(pt1), Some(pt2)) {
+                        assert_eq!(guide.len(), rounds);
+                        assert_eq!(totals(&guide), (pt1, pt2));
+                    }
+                }
+            }
+        }
+
+        // a single round can't score 1 and 9
+        assert!(synthesize(1, Some(1), Some(9)).is_err());
+        assert!(synthesize(0, Some(0), Some(0)).unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_impossible_is_proven() {
+        // every combination of two rounds
+        let types = round_types();
+        let mut possible = Vec::new();
+        for a in &types {
+            for b in &types {
+                possible.push((a.score_pt1 + b.score_pt1, a.score_pt2 + b.score_pt2));
+            }
+        }
+
+        for pt1 in 2..=18 {
+            for pt2 in 2..=18 {
+                let found = synthesize(2, Some(pt1), Some(pt2)).is_ok();
+                assert_eq!(found, possible.contains(&(pt1, pt2)));
+            }
+        }
+    }
+}
@@ -0,0 +1,321 @@
+// A round-robin tournament between Rock-Paper-Scissors bots. Every pair of
+// bots plays a match of several rounds, each round is scored like in the
+// strategy guide.
+
+use super::rules::ExpectedResult;
+use super::{determine_my_shape, score_round, HandShape};
+
+const SHAPES: [HandShape; 3] = [HandShape::Rock, HandShape::Paper, HandShape::Scissors];
+
+// Small seeded random number generator (SplitMix64), good enough for games
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Rng {
+        Rng { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform number in 0..n
+    pub fn below(&mut self, n: usize) -> usize {
+        (self.next_u64() % n as u64) as usize
+    }
+
+    pub fn shape(&mut self) -> HandShape {
+        SHAPES[self.below(SHAPES.len())]
+    }
+}
+
+// A single round from the point of view of one player
+#[derive(Clone, Copy, Debug)]
+pub struct Round {
+    pub mine: HandShape,
+    pub theirs: HandShape,
+}
+
+pub trait Strategy {
+    fn name(&self) -> String;
+
+    // The next shape to play, given all previous rounds of the match
+    fn play(&self, history: &[Round], rng: &mut Rng) -> HandShape;
+}
+
+pub struct Constant(pub HandShape);
+
+impl Strategy for Constant {
+    fn name(&self) -> String {
+        format!("Always {:?}", self.0)
+    }
+
+    fn play(&self, _history: &[Round], _rng: &mut Rng) -> HandShape {
+        self.0
+    }
+}
+
+// Rock, Paper, Scissors, Rock, ...
+pub struct Cycling;
+
+impl Strategy for Cycling {
+    fn name(&self) -> String {
+        String::from("Cycling")
+    }
+
+    fn play(&self, history: &[Round], _rng: &mut Rng) -> HandShape {
+        SHAPES[history.len() % SHAPES.len()]
+    }
+}
+
+// Beats the shape the opponent played most often so far
+pub struct FrequencyCounter;
+
+impl Strategy for FrequencyCounter {
+    fn name(&self) -> String {
+        String::from("Frequency counter")
+    }
+
+    fn play(&self, history: &[Round], rng: &mut Rng) -> HandShape {
+        let mut counts = [0; 3];
+        for round in history {
+            counts[round.theirs as usize] += 1;
+        }
+
+        let max = *counts.iter().max().unwrap();
+        let candidates: Vec<HandShape> = SHAPES
+            .iter()
+            .filter(|shape| counts[**shape as usize] == max)
+            .copied()
+            .collect();
+        let expected = candidates[rng.below(candidates.len())];
+
+        determine_my_shape(expected, ExpectedResult::Win)
+    }
+}
+
+// Keeps the shape after a win, otherwise switches to one of the other shapes
+pub struct WinStayLoseShift;
+
+impl Strategy for WinStayLoseShift {
+    fn name(&self) -> String {
+        String::from("Win-stay, lose-shift")
+    }
+
+    fn play(&self, history: &[Round], rng: &mut Rng) -> HandShape {
+        match history.last() {
+            None => rng.shape(),
+            Some(last) => {
+                if determine_my_shape(last.theirs, ExpectedResult::Win) == last.mine {
+                    last.mine
+                } else {
+                    let others: Vec<HandShape> = SHAPES
+                        .iter()
+                        .filter(|shape| **shape != last.mine)
+                        .copied()
+                        .collect();
+                    others[rng.below(others.len())]
+                }
+            }
+        }
+    }
+}
+
+// Plays the shapes of the strategy guide in order, starting over at the end
+pub struct ReplayGuide(pub Vec<HandShape>);
+
+impl Strategy for ReplayGuide {
+    fn name(&self) -> String {
+        String::from("Strategy guide")
+    }
+
+    fn play(&self, history: &[Round], rng: &mut Rng) -> HandShape {
+        if self.0.is_empty() {
+            return rng.shape();
+        }
+        self.0[history.len() % self.0.len()]
+    }
+}
+
+pub struct Random;
+
+impl Strategy for Random {
+    fn name(&self) -> String {
+        String::from("Random")
+    }
+
+    fn play(&self, _history: &[Round], rng: &mut Rng) -> HandShape {
+        rng.shape()
+    }
+}
+
+#[derive(Debug)]
+pub struct Standing {
+    pub name: String,
+    pub points: i32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+// The total scores of both bots in a match
+fn play_match(
+    first: &dyn Strategy,
+    second: &dyn Strategy,
+    rounds: usize,
+    rng: &mut Rng,
+) -> (i32, i32) {
+    let mut first_history = Vec::new();
+    let mut second_history = Vec::new();
+    let mut scores = (0, 0);
+
+    for _ in 0..rounds {
+        let first_shape = first.play(&first_history, rng);
+        let second_shape = second.play(&second_history, rng);
+
+        scores.0 += score_round(second_shape, first_shape);
+        scores.1 += score_round(first_shape, second_shape);
+
+        first_history.push(Round {
+            mine: first_shape,
+            theirs: second_shape,
+        });
+        second_history.push(Round {
+            mine: second_shape,
+            theirs: first_shape,
+        });
+    }
+    scores
+}
+
+// Every bot plays every other bot once. The standings are sorted by match
+// wins, then by points.
+pub fn round_robin(bots: &[Box<dyn Strategy>], rounds: usize, seed: u64) -> Vec<Standing> {
+    let mut rng = Rng::new(seed);
+    let mut standings: Vec<Standing> = bots
+        .iter()
+        .map(|bot| Standing {
+            name: bot.name(),
+            points: 0,
+            wins: 0,
+            draws: 0,
+            losses: 0,
+        })
+        .collect();
+
+    for i in 0..bots.len() {
+        for j in i + 1..bots.len() {
+            let (score_i, score_j) =
+                play_match(bots[i].as_ref(), bots[j].as_ref(), rounds, &mut rng);
+
+            standings[i].points += score_i;
+            standings[j].points += score_j;
+
+            if score_i > score_j {
+                standings[i].wins += 1;
+                standings[j].losses += 1;
+            } else if score_i < score_j {
+                standings[i].losses += 1;
+                standings[j].wins += 1;
+            } else {
+                standings[i].draws += 1;
+                standings[j].draws += 1;
+            }
+        }
+    }
+
+    standings.sort_by(|a, b| b.wins.cmp(&a.wins).then(b.points.cmp(&a.points)));
+    standings
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rng() {
+        let mut rng = Rng::new(42);
+        let mut other = Rng::new(42);
+        for _ in 0..100 {
+            let n = rng.below(3);
+            assert!(n < 3);
+            assert_eq!(n, other.below(3));
+        }
+    }
+
+    #[test]
+    fn test_play_match() {
+        let mut rng = Rng::new(0);
+
+        // rock loses against paper every time
+        let scores = play_match(
+            &Constant(HandShape::Rock),
+            &Constant(HandShape::Paper),
+            10,
+            &mut rng,
+        );
+        assert_eq!(scores, (10, 80));
+
+        // the counter beats a constant opponent after the first round
+        let scores = play_match(
+            &FrequencyCounter,
+            &Constant(HandShape::Scissors),
+            10,
+            &mut rng,
+        );
+        assert!(scores.0 >= 9 * 7);
+
+        // cycling ties against itself
+        let scores = play_match(&Cycling, &Cycling, 9, &mut rng);
+        assert_eq!(scores.0, scores.1);
+    }
+
+    #[test]
+    fn test_win_stay_lose_shift() {
+        let mut rng = Rng::new(1);
+        let won = [Round {
+            mine: HandShape::Paper,
+            theirs: HandShape::Rock,
+        }];
+        assert_eq!(WinStayLoseShift.play(&won, &mut rng), HandShape::Paper);
+
+        let lost = [Round {
+            mine: HandShape::Paper,
+            theirs: HandShape::Scissors,
+        }];
+        for _ in 0..10 {
+            assert_ne!(WinStayLoseShift.play(&lost, &mut rng), HandShape::Paper);
+        }
+    }
+
+    #[test]
+    fn test_round_robin() {
+        let bots: Vec<Box<dyn Strategy>> = vec![
+            Box::new(Constant(HandShape::Rock)),
+            Box::new(Cycling),
+            Box::new(FrequencyCounter),
+            Box::new(WinStayLoseShift),
+            Box::new(ReplayGuide(vec![HandShape::Paper, HandShape::Scissors])),
+            Box::new(Random),
+        ];
+
+        let standings = round_robin(&bots, 100, 7);
+        assert_eq!(standings.len(), bots.len());
+        for standing in &standings {
+            assert_eq!(standing.wins + standing.draws + standing.losses, 5);
+        }
+
+        // seeded, so the tournament is reproducible
+        let again = round_robin(&bots, 100, 7);
+        for (a, b) in standings.iter().zip(&again) {
+            assert_eq!(a.name, b.name);
+            assert_eq!(a.points, b.points);
+        }
+    }
+}
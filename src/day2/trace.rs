@@ -0,0 +1,147 @@
+// Explains the score of every round of the strategy guide, to audit the
+// totals line by line.
+
+use super::encoding::Encoding;
+use super::rules::ExpectedResult;
+use super::{determine_my_shape, rock_paper_scissors, score_round, shape_idx, HandShape};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Part {
+    One,
+    Two,
+}
+
+pub struct TraceRow {
+    pub line: usize,
+    pub elf_shape: HandShape,
+    pub my_shape: HandShape,
+    // only known in part two
+    pub required: Option<ExpectedResult>,
+    pub outcome: ExpectedResult,
+    pub shape_score: i32,
+    pub outcome_score: i32,
+    pub total: i32,
+}
+
+pub fn trace(guide: &[(String, String)], encoding: &Encoding, part: Part) -> Vec<TraceRow> {
+    let rules = rock_paper_scissors();
+    let mut total = 0;
+
+    guide
+        .iter()
+        .enumerate()
+        .map(|(idx, (elf_token, my_token))| {
+            let elf_shape = encoding.elf_shape(elf_token).expect("Unknown elf token!");
+            let (my_shape, required) = match part {
+                Part::One => (
+                    encoding.my_shape(my_token).expect("Unknown shape token!"),
+                    None,
+                ),
+                Part::Two => {
+                    let result = encoding.result(my_token).expect("Unknown result token!");
+                    (determine_my_shape(elf_shape, result), Some(result))
+                }
+            };
+
+            let outcome = rules.outcome(shape_idx(elf_shape), shape_idx(my_shape));
+            let shape_score = rules.shape_score(shape_idx(my_shape));
+            let outcome_score = outcome.score();
+            total += score_round(elf_shape, my_shape);
+
+            TraceRow {
+                line: idx + 1,
+                elf_shape,
+                my_shape,
+                required,
+                outcome,
+                shape_score,
+                outcome_score,
+                total,
+            }
+        })
+        .collect()
+}
+
+fn columns(row: &TraceRow) -> [String; 8] {
+    [
+        row.line.to_string(),
+        format!("{:?}", row.elf_shape),
+        format!("{:?}", row.my_shape),
+        row.required.map(|r| r.name()).unwrap_or("-").to_string(),
+        row.outcome.name().to_string(),
+        row.shape_score.to_string(),
+        row.outcome_score.to_string(),
+        row.total.to_string(),
+    ]
+}
+
+const HEADER: [&str; 8] = [
+    "line", "elf", "me", "required", "outcome", "shape", "outcome", "total",
+];
+
+pub fn to_csv(rows: &[TraceRow]) -> String {
+    let mut csv = HEADER.join(",") + "\n";
+    for row in rows {
+        csv += &(columns(row).join(",") + "\n");
+    }
+    csv
+}
+
+pub fn to_table(rows: &[TraceRow]) -> String {
+    let format_row = |cols: &[String]| -> String {
+        format!(
+            "{:>6} {:>9} {:>9} {:>9} {:>8} {:>6} {:>8} {:>8}\n",
+            cols[0], cols[1], cols[2], cols[3], cols[4], cols[5], cols[6], cols[7]
+        )
+    };
+
+    let mut table = format_row(&HEADER.map(String::from));
+    for row in rows {
+        table += &format_row(&columns(row));
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn test_guide() -> Vec<(String, String)> {
+        [("A", "Y"), ("B", "X"), ("C", "Z")]
+            .iter()
+            .map(|(elf, me)| (elf.to_string(), me.to_string()))
+            .collect()
+    }
+
+    #[test]
+    fn test_trace() {
+        let encoding = Encoding::standard();
+
+        let rows = trace(&test_guide(), &encoding, Part::One);
+        assert_eq!(rows.len(), 3);
+        assert_eq!(rows[0].my_shape, HandShape::Paper);
+        assert_eq!(rows[0].outcome, ExpectedResult::Win);
+        assert_eq!((rows[0].shape_score, rows[0].outcome_score), (2, 6));
+        assert_eq!(rows[1].outcome, ExpectedResult::Lose);
+        assert_eq!(rows[2].total, 15);
+
+        let rows = trace(&test_guide(), &encoding, Part::Two);
+        assert_eq!(rows[0].required, Some(ExpectedResult::Draw));
+        assert_eq!(rows[0].my_shape, HandShape::Rock);
+        assert_eq!(rows[2].total, 12);
+    }
+
+    #[test]
+    fn test_csv() {
+        let rows = trace(&test_guide(), &Encoding::standard(), Part::Two);
+        let csv = to_csv(&rows);
+        let lines: Vec<&str> = csv.lines().collect();
+
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines[0], "line,elf,me,required,outcome,shape,outcome,total");
+        assert_eq!(lines[1], "1,Rock,Rock,draw,draw,1,3,4");
+        assert_eq!(lines[3], "3,Scissors,Rock,win,win,1,6,12");
+
+        assert_eq!(to_table(&rows).lines().count(), 4);
+    }
+}
@@ -0,0 +1,154 @@
+// Finds the groups of three elves when the order of the rucksacks is unknown.
+// Every group must share exactly one item, the badge. All triples sharing
+// exactly one item are enumerated first, pairs without a common item are
+// pruned early. The partition is then searched as an exact cover, always
+// continuing with the rucksack that fits into the fewest remaining triples.
+
+use super::itemset::ItemSet;
+
+pub struct Discovery {
+    // indices of the rucksacks of every group
+    pub groups: Vec<[usize; 3]>,
+    pub unique: bool,
+}
+
+fn valid_triples(rucksacks: &[ItemSet]) -> Vec<[usize; 3]> {
+    let n = rucksacks.len();
+    let mut triples = Vec::new();
+
+    for i in 0..n {
+        for j in i + 1..n {
+            let pair = rucksacks[i].intersection(rucksacks[j]);
+            if pair.is_empty() {
+                continue;
+            }
+            for (k, set) in rucksacks.iter().enumerate().skip(j + 1) {
+                if pair.intersection(*set).len() == 1 {
+                    triples.push([i, j, k]);
+                }
+            }
+        }
+    }
+    triples
+}
+
+struct Search {
+    triples: Vec<[usize; 3]>,
+    // the triples containing each rucksack
+    containing: Vec<Vec<usize>>,
+    used: Vec<bool>,
+    chosen: Vec<usize>,
+    solutions: Vec<Vec<usize>>,
+}
+
+impl Search {
+    fn is_free(&self, triple: usize) -> bool {
+        self.triples[triple].iter().all(|r| !self.used[*r])
+    }
+
+    fn set_used(&mut self, triple: usize, used: bool) {
+        for r in self.triples[triple] {
+            self.used[r] = used;
+        }
+    }
+
+    fn run(&mut self) {
+        // a second solution is enough to know the partition is not unique
+        if self.solutions.len() > 1 {
+            return;
+        }
+
+        let candidates = (0..self.used.len())
+            .filter(|r| !self.used[*r])
+            .map(|r| {
+                self.containing[r]
+                    .iter()
+                    .copied()
+                    .filter(|t| self.is_free(*t))
+                    .collect::<Vec<usize>>()
+            })
+            .min_by_key(|free| free.len());
+
+        let Some(candidates) = candidates else {
+            self.solutions.push(self.chosen.clone());
+            return;
+        };
+
+        for triple in candidates {
+            self.set_used(triple, true);
+            self.chosen.push(triple);
+            self.run();
+            self.chosen.pop();
+            self.set_used(triple, false);
+        }
+    }
+}
+
+// A partition of the rucksacks into groups sharing exactly one item, None if
+// there is none
+pub fn discover(rucksacks: &[ItemSet]) -> Option<Discovery> {
+    if !rucksacks.len().is_multiple_of(3) {
+        return None;
+    }
+
+    let triples = valid_triples(rucksacks);
+    let mut containing = vec![Vec::new(); rucksacks.len()];
+    for (idx, triple) in triples.iter().enumerate() {
+        for r in triple {
+            containing[*r].push(idx);
+        }
+    }
+
+    let mut search = Search {
+        triples,
+        containing,
+        used: vec![false; rucksacks.len()],
+        chosen: Vec::new(),
+        solutions: Vec::new(),
+    };
+    search.run();
+
+    let unique = search.solutions.len() == 1;
+    let first = search.solutions.first()?;
+
+    let mut groups: Vec<[usize; 3]> = first.iter().map(|t| search.triples[*t]).collect();
+    groups.sort();
+    Some(Discovery { groups, unique })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sets(lines: &[&str]) -> Vec<ItemSet> {
+        lines
+            .iter()
+            .map(|line| line.bytes().map(|b| b as u32).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_discover() {
+        // two groups with the badges a and b, interleaved
+        let rucksacks = sets(&["axy", "bpq", "azw", "brs", "auv", "btu"]);
+        let discovery = discover(&rucksacks).unwrap();
+        assert_eq!(discovery.groups, vec![[0, 2, 4], [1, 3, 5]]);
+        assert!(discovery.unique);
+    }
+
+    #[test]
+    fn test_ambiguous() {
+        // every rucksack shares exactly a with any two others
+        let rucksacks = sets(&["ab", "ac", "ad", "ae", "af", "ag"]);
+        let discovery = discover(&rucksacks).unwrap();
+        assert_eq!(discovery.groups.len(), 2);
+        assert!(!discovery.unique);
+    }
+
+    #[test]
+    fn test_impossible() {
+        assert!(discover(&sets(&["ab", "cd", "ef"])).is_none());
+        assert!(discover(&sets(&["ab", "ab", "ab"])).is_none());
+        assert!(discover(&sets(&["a", "a"])).is_none());
+    }
+}
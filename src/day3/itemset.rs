@@ -0,0 +1,133 @@
+// A set of item priorities stored as a bitmask, bit p is set if an item with
+// priority p is contained. Priorities range from 0 to 255, which covers the 52
+// priorities of the puzzle as well as every byte value.
+
+const WORDS: usize = 4;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct ItemSet([u64; WORDS]);
+
+impl ItemSet {
+    pub const MAX_PRIORITY: u32 = 64 * WORDS as u32 - 1;
+
+    pub fn new() -> ItemSet {
+        ItemSet([0; WORDS])
+    }
+
+    pub fn insert(&mut self, priority: u32) {
+        assert!(priority <= Self::MAX_PRIORITY, "Priority out of range!");
+        self.0[priority as usize / 64] |= 1 << (priority % 64);
+    }
+
+    pub fn union(self, other: ItemSet) -> ItemSet {
+        let mut result = self;
+        for (word, other_word) in result.0.iter_mut().zip(other.0) {
+            *word |= other_word;
+        }
+        result
+    }
+
+    pub fn intersection(self, other: ItemSet) -> ItemSet {
+        let mut result = self;
+        for (word, other_word) in result.0.iter_mut().zip(other.0) {
+            *word &= other_word;
+        }
+        result
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.iter().all(|word| *word == 0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.iter().map(|word| word.count_ones() as usize).sum()
+    }
+
+    // The priorities in increasing order
+    pub fn iter(&self) -> ItemSetIter {
+        ItemSetIter {
+            words: self.0,
+            idx: 0,
+        }
+    }
+}
+
+impl FromIterator<u32> for ItemSet {
+    fn from_iter<I: IntoIterator<Item = u32>>(iter: I) -> ItemSet {
+        let mut set = ItemSet::new();
+        for priority in iter {
+            set.insert(priority);
+        }
+        set
+    }
+}
+
+pub struct ItemSetIter {
+    words: [u64; WORDS],
+    idx: usize,
+}
+
+impl Iterator for ItemSetIter {
+    type Item = u32;
+
+    fn next(&mut self) -> Option<u32> {
+        while self.idx < WORDS && self.words[self.idx] == 0 {
+            self.idx += 1;
+        }
+        if self.idx == WORDS {
+            return None;
+        }
+
+        let word = &mut self.words[self.idx];
+        let priority = 64 * self.idx as u32 + word.trailing_zeros();
+        // clear the lowest set bit
+        *word &= *word - 1;
+        Some(priority)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_insert() {
+        let mut set = ItemSet::new();
+        assert!(set.is_empty());
+
+        set.insert(1);
+        set.insert(52);
+        set.insert(52);
+        assert!(!set.is_empty());
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.iter().collect::<Vec<u32>>(), vec![1, 52]);
+    }
+
+    #[test]
+    fn test_union_intersection() {
+        let lhs: ItemSet = [1, 5, 20].into_iter().collect();
+        let rhs: ItemSet = [5, 20, 30].into_iter().collect();
+
+        assert_eq!(
+            lhs.union(rhs).iter().collect::<Vec<u32>>(),
+            vec![1, 5, 20, 30]
+        );
+        assert_eq!(lhs.union(ItemSet::new()), lhs);
+
+        assert_eq!(
+            lhs.intersection(rhs).iter().collect::<Vec<u32>>(),
+            vec![5, 20]
+        );
+        assert!(lhs.intersection(ItemSet::new()).is_empty());
+    }
+
+    #[test]
+    fn test_iter() {
+        let set: ItemSet = [63, 0, 27].into_iter().collect();
+        assert_eq!(set.iter().collect::<Vec<u32>>(), vec![0, 27, 63]);
+
+        let set: ItemSet = [255, 64, 128, 1].into_iter().collect();
+        assert_eq!(set.iter().collect::<Vec<u32>>(), vec![1, 64, 128, 255]);
+        assert_eq!(ItemSet::new().iter().next(), None);
+    }
+}
@@ -1,100 +1,578 @@
-use std::collections::HashSet;
+use std::env;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
 
-use aoc::read_lines;
+mod badges;
+mod itemset;
+mod priority;
+mod repair;
+mod strict;
 
-fn item_priority(item: char) -> u32 {
+use itemset::ItemSet;
+use priority::{Letters, PriorityScheme};
+use strict::Issue;
+
+fn try_item_priority(item: char) -> Option<u32> {
     let item_nr = item as u32;
 
     if (item_nr >= 'A' as u32) && (item_nr <= 'Z' as u32) {
-        return item_nr - ('A' as u32) + 27;
+        Some(item_nr - ('A' as u32) + 27)
     } else if (item_nr >= 'a' as u32) && (item_nr <= 'z' as u32) {
-        return item_nr - ('a' as u32) + 1;
+        Some(item_nr - ('a' as u32) + 1)
     } else {
-        panic!("Can't prioritize unexpected item!");
+        None
+    }
+}
+
+// The item with the given priority
+fn priority_item(priority: u32) -> char {
+    match priority {
+        1..=26 => char::from(b'a' + (priority - 1) as u8),
+        27..=52 => char::from(b'A' + (priority - 27) as u8),
+        _ => panic!("No item with priority {}!", priority),
     }
 }
 
-fn find_duplicated(left: &[char], right: &[char]) -> char {
-    let mut map_left = HashSet::new();
+fn item_set(scheme: &dyn PriorityScheme, items: &[char]) -> ItemSet {
+    items
+        .iter()
+        .map(|item| {
+            scheme
+                .priority(*item)
+                .expect("Can't prioritize unexpected item!")
+        })
+        .collect()
+}
+
+// The items contained in all of the given lists
+fn find_common(scheme: &dyn PriorityScheme, lists: &[&[char]]) -> ItemSet {
+    lists
+        .iter()
+        .map(|items| item_set(scheme, items))
+        .reduce(|common, set| common.intersection(set))
+        .unwrap_or_default()
+}
+
+// All items shared by the lists, ordered by priority
+fn shared_items(scheme: &dyn PriorityScheme, lists: &[&[char]]) -> Vec<char> {
+    find_common(scheme, lists)
+        .iter()
+        .map(|priority| scheme.item(priority).unwrap())
+        .collect()
+}
 
-    for item in left {
-        map_left.insert(item);
+// The priority of the single item shared by all lists
+fn common_priority(scheme: &dyn PriorityScheme, lists: &[&[char]]) -> Result<u32, Issue> {
+    let common = find_common(scheme, lists);
+    match common.len() {
+        0 => Err(Issue::NoSharedItem),
+        1 => Ok(common.iter().next().unwrap()),
+        _ => Err(Issue::SeveralSharedItems(shared_items(scheme, lists))),
     }
+}
 
-    for item in right {
-        if map_left.contains(item) {
-            return item.clone();
-        }
+// The first item the scheme can't prioritize
+fn check_items(scheme: &dyn PriorityScheme, items: &[char]) -> Result<(), Issue> {
+    match items.iter().find(|item| scheme.priority(**item).is_none()) {
+        Some(item) => Err(Issue::InvalidItem(*item)),
+        None => Ok(()),
     }
-    panic!("No key found that exists in both sides!");
 }
 
-fn solve_pt1(file: &str) -> u32 {
+// The compartments of a rucksack with only valid items
+fn check_rucksack<'a>(
+    scheme: &dyn PriorityScheme,
+    items: &'a [char],
+) -> Result<(&'a [char], &'a [char]), Issue> {
+    check_items(scheme, items)?;
+    compartments(items).ok_or(Issue::OddLength(items.len()))
+}
+
+// Lines are numbered from one
+fn describe_lines(first_line: usize, last_line: usize, issue: &Issue) -> String {
+    if first_line == last_line {
+        format!("line {}: {}", first_line, issue.describe())
+    } else {
+        format!(
+            "group of lines {}-{}: {}",
+            first_line,
+            last_line,
+            issue.describe()
+        )
+    }
+}
+
+// The two halves of a rucksack, None if the number of items is odd
+fn compartments(items: &[char]) -> Option<(&[char], &[char])> {
+    if !items.len().is_multiple_of(2) {
+        return None;
+    }
+    Some(items.split_at(items.len() / 2))
+}
+
+// Sum of the priorities of the items shared by the compartments of every
+// rucksack. Fails for the first rucksack without exactly one shared item.
+fn solve_pt1(file: &str, scheme: &dyn PriorityScheme) -> Result<u32, String> {
     let mut total_sum = 0;
 
-    let lines = read_lines(file).unwrap();
-    for rline in lines {
-        if let Ok(line) = rline {
-            let all_chars: Vec<char> = line.chars().collect();
-            assert!(all_chars.len() % 2 == 0);
+    for (idx, all_chars) in read_rucksacks(file, scheme)?.iter().enumerate() {
+        total_sum += check_rucksack(scheme, all_chars)
+            .and_then(|(head, tail)| common_priority(scheme, &[head, tail]))
+            .map_err(|issue| describe_lines(idx + 1, idx + 1, &issue))?;
+    }
+    Ok(total_sum)
+}
 
-            let (head, tail) = all_chars.split_at(all_chars.len() / 2);
-            let item = find_duplicated(head, tail);
-            total_sum += item_priority(item);
+// The lines are decoded by the scheme, so they don't need to be valid UTF-8
+fn read_rucksacks(file: &str, scheme: &dyn PriorityScheme) -> Result<Vec<Vec<char>>, String> {
+    let reader = File::open(file)
+        .map(BufReader::new)
+        .map_err(|err| format!("Can't read {}: {}", file, err))?;
+
+    reader
+        .split(b'\n')
+        .map(|line| line.map(|l| scheme.decode(&l)))
+        .collect::<Result<_, _>>()
+        .map_err(|err| format!("Can't read {}: {}", file, err))
+}
+
+// Sum of the priorities of the items shared by every group of `group_size`
+// consecutive rucksacks. Fails for the first group without exactly one shared
+// item.
+fn solve_pt2(file: &str, scheme: &dyn PriorityScheme, group_size: usize) -> Result<u32, String> {
+    let rucksacks = read_rucksacks(file, scheme)?;
+
+    if group_size == 0 || !rucksacks.len().is_multiple_of(group_size) {
+        return Err(format!(
+            "{} rucksacks can't be split into groups of {}",
+            rucksacks.len(),
+            group_size
+        ));
+    }
+
+    for (idx, items) in rucksacks.iter().enumerate() {
+        check_items(scheme, items).map_err(|issue| describe_lines(idx + 1, idx + 1, &issue))?;
+    }
+
+    let mut total_sum = 0;
+    for (group_idx, group) in rucksacks.chunks(group_size).enumerate() {
+        let lists: Vec<&[char]> = group.iter().map(|items| items.as_slice()).collect();
+        let first_line = group_idx * group_size + 1;
+        total_sum += common_priority(scheme, &lists)
+            .map_err(|issue| describe_lines(first_line, first_line + group_size - 1, &issue))?;
+    }
+    Ok(total_sum)
+}
+
+// Strips the --scheme option from the arguments
+fn parse_options(args: &[String]) -> (Box<dyn PriorityScheme>, Vec<String>) {
+    let mut scheme: Box<dyn PriorityScheme> = Box::new(Letters);
+    let mut positional = Vec::new();
+
+    let mut it = args.iter();
+    while let Some(arg) = it.next() {
+        match arg.as_str() {
+            "--scheme" => {
+                let name = it.next().expect("Expected priority scheme!");
+                scheme = priority::build(name).unwrap();
+            }
+            _ => positional.push(arg.clone()),
         }
     }
-    total_sum
+    (scheme, positional)
 }
 
-fn find_threeway_duplicated(left: &[char], mid: &[char], right: &[char]) -> char {
-    let mut map_left = HashSet::new();
-    let mut map_mid = HashSet::new();
+fn print_strict(file: &str, scheme: &dyn PriorityScheme, group_size: usize) {
+    let rucksacks = read_rucksacks(file, scheme).unwrap();
+    let reports = match strict::check(&rucksacks, scheme, group_size) {
+        Ok(reports) => reports,
+        Err(e) => {
+            println!("{}", e);
+            return;
+        }
+    };
 
-    for item in left {
-        map_left.insert(item);
+    for report in &reports {
+        println!(
+            "{}",
+            describe_lines(report.first_line, report.last_line, &report.issue)
+        );
     }
+    println!("{} issues in {} rucksacks", reports.len(), rucksacks.len());
+}
 
-    for item in mid {
-        map_mid.insert(item);
+fn format_items(items: &[char]) -> String {
+    if items.is_empty() {
+        String::from("none")
+    } else {
+        items.iter().collect()
     }
+}
+
+// Prints every item shared by the compartments of each rucksack, all of them
+// together and every item shared by each group of `group_size` rucksacks
+fn print_shared(file: &str, scheme: &dyn PriorityScheme, group_size: usize) {
+    let rucksacks = read_rucksacks(file, scheme).unwrap();
 
-    for item in right {
-        if map_left.contains(item) && map_mid.contains(item) {
-            return item.clone();
+    let mut any_line = ItemSet::new();
+    for (idx, items) in rucksacks.iter().enumerate() {
+        match check_rucksack(scheme, items) {
+            Ok((head, tail)) => {
+                any_line = any_line.union(find_common(scheme, &[head, tail]));
+                println!(
+                    "line {}: {}",
+                    idx + 1,
+                    format_items(&shared_items(scheme, &[head, tail]))
+                );
+            }
+            Err(issue) => println!("{}", describe_lines(idx + 1, idx + 1, &issue)),
         }
     }
+    let any_line: Vec<char> = any_line
+        .iter()
+        .map(|priority| scheme.item(priority).unwrap())
+        .collect();
+    println!("shared on any line: {}", format_items(&any_line));
 
-    panic!("No key found that exists in all three!");
+    if group_size == 0 || !rucksacks.len().is_multiple_of(group_size) {
+        println!(
+            "{} rucksacks can't be split into groups of {}",
+            rucksacks.len(),
+            group_size
+        );
+        return;
+    }
+    for (group_idx, group) in rucksacks.chunks(group_size).enumerate() {
+        let first_line = group_idx * group_size + 1;
+        let last_line = first_line + group_size - 1;
+        if let Some(issue) = group
+            .iter()
+            .find_map(|items| check_items(scheme, items).err())
+        {
+            println!("{}", describe_lines(first_line, last_line, &issue));
+            continue;
+        }
+        let lists: Vec<&[char]> = group.iter().map(|items| items.as_slice()).collect();
+        println!(
+            "group of lines {}-{}: {}",
+            first_line,
+            last_line,
+            format_items(&shared_items(scheme, &lists))
+        );
+    }
 }
 
-fn solve_pt2(file: &str) -> u32 {
-    let mut total_sum = 0;
+fn print_badges(file: &str, scheme: &dyn PriorityScheme) {
+    let rucksacks = read_rucksacks(file, scheme).unwrap();
+    let sets: Vec<ItemSet> = rucksacks
+        .iter()
+        .map(|items| item_set(scheme, items))
+        .collect();
+
+    let Some(discovery) = badges::discover(&sets) else {
+        println!("The rucksacks can't be split into groups sharing one item");
+        return;
+    };
 
-    let lines: Vec<String> = read_lines(file).unwrap().collect::<Result<_, _>>().unwrap();
+    let mut total = 0;
+    for group in &discovery.groups {
+        let lists: Vec<&[char]> = group.iter().map(|r| rucksacks[*r].as_slice()).collect();
+        let badge = common_priority(scheme, &lists).expect("Expected a single badge!");
+        total += badge;
 
-    for window in lines.windows(3).step_by(3) {
-        let chars0 = &window[0].chars().collect::<Vec<char>>();
-        let chars1 = &window[1].chars().collect::<Vec<char>>();
-        let chars2 = &window[2].chars().collect::<Vec<char>>();
+        let lines: Vec<String> = group.iter().map(|r| (r + 1).to_string()).collect();
+        println!(
+            "lines {}: badge {}",
+            lines.join(", "),
+            scheme.item(badge).unwrap()
+        );
+    }
+    println!("The sum of the badges is {}", total);
+    if discovery.unique {
+        println!("The grouping is unique");
+    } else {
+        println!("The grouping is not unique");
+    }
+}
 
-        let item = find_threeway_duplicated(&chars0, &chars1, &chars2);
+fn print_repair(file: &str, scheme: &dyn PriorityScheme, group_size: usize) {
+    let rucksacks = read_rucksacks(file, scheme).unwrap();
+    if group_size == 0 || !rucksacks.len().is_multiple_of(group_size) {
+        println!(
+            "{} rucksacks can't be split into groups of {}",
+            rucksacks.len(),
+            group_size
+        );
+        return;
+    }
+
+    let mut total = 0;
+    let mut minimal = true;
+    for (group_idx, group) in rucksacks.chunks(group_size).enumerate() {
+        // rucksacks of groups which can't be repaired are left as they are
+        let repair = match repair::repair_group(group) {
+            Ok(repair) => repair,
+            Err(failure) => {
+                println!("group {} {}", group_idx + 1, failure.describe());
+                continue;
+            }
+        };
+        total += repair.swaps;
+        if !repair.minimal {
+            println!(
+                "group {}: search limit reached, the swaps are only minimal for each rucksack",
+                group_idx + 1
+            );
+            minimal = false;
+        }
 
-        total_sum += item_priority(item);
+        for (idx, (before, after)) in group.iter().zip(&repair.rucksacks).enumerate() {
+            if before != after {
+                println!(
+                    "line {}: {} -> {}",
+                    group_idx * group_size + idx + 1,
+                    before.iter().collect::<String>(),
+                    after.iter().collect::<String>()
+                );
+            }
+        }
+    }
+    if minimal {
+        println!("{} swaps in total", total);
+    } else {
+        println!("{} swaps in total, not necessarily the fewest", total);
     }
-    total_sum
 }
 
 fn main() {
-    let solution_pt1 = solve_pt1("data/day3/input");
-    println!("The solution to part one is {}", solution_pt1);
+    let args: Vec<String> = env::args().collect();
+    let (scheme, args) = parse_options(&args);
+    let scheme = scheme.as_ref();
+
+    if args.len() > 2 && args[1] == "groups" {
+        // day3 groups <size> [file]
+        let group_size = args[2].parse::<usize>().expect("Expected group size!");
+        let file = args.get(3).map_or("data/day3/input", |s| s.as_str());
+
+        match solve_pt2(file, scheme, group_size) {
+            Ok(total) => println!(
+                "The sum of the badges of groups of {} is {}",
+                group_size, total
+            ),
+            Err(err) => eprintln!("{}", err),
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "shared" {
+        // day3 shared [group size] [file], all shared items of every
+        // rucksack and group
+        let group_size = args
+            .get(2)
+            .map(|s| s.parse::<usize>().expect("Expected group size!"))
+            .unwrap_or(3);
+        let file = args.get(3).map_or("data/day3/input", |s| s.as_str());
 
-    let solution_pt2: u32 = solve_pt2("data/day3/input");
-    println!("The solution to part two is {}", solution_pt2);
+        print_shared(file, scheme, group_size);
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "strict" {
+        // day3 strict [group size] [file]
+        let group_size = args
+            .get(2)
+            .map(|s| s.parse::<usize>().expect("Expected group size!"))
+            .unwrap_or(3);
+        let file = args.get(3).map_or("data/day3/input", |s| s.as_str());
+
+        print_strict(file, scheme, group_size);
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "repair" {
+        // day3 repair [group size] [file], the fewest swaps within every group
+        let group_size = args
+            .get(2)
+            .map(|s| s.parse::<usize>().expect("Expected group size!"))
+            .unwrap_or(3);
+        let file = args.get(3).map_or("data/day3/input", |s| s.as_str());
+
+        print_repair(file, scheme, group_size);
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "badges" {
+        // day3 badges [file], the rucksacks may be in any order
+        let file = args.get(2).map_or("data/day3/input", |s| s.as_str());
+
+        print_badges(file, scheme);
+        return;
+    }
+
+    match solve_pt1("data/day3/input", scheme) {
+        Ok(solution_pt1) => println!("The solution to part one is {}", solution_pt1),
+        Err(err) => eprintln!("Part one: {}", err),
+    }
+
+    match solve_pt2("data/day3/input", scheme, 3) {
+        Ok(solution_pt2) => println!("The solution to part two is {}", solution_pt2),
+        Err(err) => eprintln!("Part two: {}", err),
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use priority::Table;
+    use std::collections::HashSet;
+
+    fn item_priority(item: char) -> u32 {
+        Letters
+            .priority(item)
+            .expect("Can't prioritize unexpected item!")
+    }
+
+    // The previous implementation based on hash sets, kept as a reference
+    fn find_duplicated(left: &[char], right: &[char]) -> char {
+        let mut map_left = HashSet::new();
+
+        for item in left {
+            map_left.insert(item);
+        }
+
+        for item in right {
+            if map_left.contains(item) {
+                return *item;
+            }
+        }
+        panic!("No key found that exists in both sides!");
+    }
+
+    fn find_threeway_duplicated(left: &[char], mid: &[char], right: &[char]) -> char {
+        let mut map_left = HashSet::new();
+        let mut map_mid = HashSet::new();
+
+        for item in left {
+            map_left.insert(item);
+        }
+
+        for item in mid {
+            map_mid.insert(item);
+        }
+
+        for item in right {
+            if map_left.contains(item) && map_mid.contains(item) {
+                return *item;
+            }
+        }
+
+        panic!("No key found that exists in all three!");
+    }
+
+    #[test]
+    fn test_against_reference() {
+        let rucksacks = read_rucksacks("data/day3/input", &Letters).unwrap();
+
+        let mut total_pt1 = 0;
+        for chars in &rucksacks {
+            let (head, tail) = chars.split_at(chars.len() / 2);
+            let item = find_duplicated(head, tail);
+            assert_eq!(
+                common_priority(&Letters, &[head, tail]),
+                Ok(item_priority(item))
+            );
+            total_pt1 += item_priority(item);
+        }
+        assert_eq!(solve_pt1("data/day3/input", &Letters), Ok(total_pt1));
+
+        let mut total_pt2 = 0;
+        for group in rucksacks.chunks(3) {
+            let item = find_threeway_duplicated(&group[0], &group[1], &group[2]);
+            total_pt2 += item_priority(item);
+        }
+        assert_eq!(solve_pt2("data/day3/input", &Letters, 3), Ok(total_pt2));
+    }
+
+    #[test]
+    fn test_priority_item() {
+        for priority in 1..=52 {
+            assert_eq!(item_priority(priority_item(priority)), priority);
+        }
+        assert_eq!(try_item_priority('1'), None);
+    }
+
+    #[test]
+    fn test_shared_items() {
+        let left = vec!['a', 'X', 'b', 'c'];
+        let right = vec!['X', 'c', 'd', 'a'];
+        assert_eq!(
+            shared_items(&Letters, &[&left, &right]),
+            vec!['a', 'c', 'X']
+        );
+        assert_eq!(shared_items(&Letters, &[&left, &['z']]), vec![]);
+    }
+
+    #[test]
+    fn test_find_common() {
+        let left = vec!['a', 't', 'x', 'x', 'x'];
+        let mid = vec!['z', 't', 'a', 'x', 'c'];
+        let right = vec!['c', 'b', 't', 'b', 'b'];
+        assert_eq!(
+            find_common(&Letters, &[&left, &mid, &right]),
+            item_set(&Letters, &['t'])
+        );
+        assert_eq!(
+            find_common(&Letters, &[&left, &mid]),
+            item_set(&Letters, &['a', 't', 'x'])
+        );
+        assert_eq!(find_common(&Letters, &[&right]), item_set(&Letters, &right));
+        assert!(find_common(&Letters, &[]).is_empty());
+
+        assert_eq!(
+            common_priority(&Letters, &[&left, &mid, &right]),
+            Ok(item_priority('t'))
+        );
+        assert_eq!(
+            common_priority(&Letters, &[&left, &mid]),
+            Err(Issue::SeveralSharedItems(vec!['a', 't', 'x']))
+        );
+        assert_eq!(
+            common_priority(&Letters, &[&left, &['b']]),
+            Err(Issue::NoSharedItem)
+        );
+    }
+
+    #[test]
+    fn test_group_size() {
+        // the first pair shares several items
+        assert_eq!(
+            solve_pt2("data/day3/test", &Letters, 2),
+            Err(String::from(
+                "group of lines 1-2: several shared items (frsFM)"
+            ))
+        );
+        assert!(solve_pt2("data/day3/test", &Letters, 4).is_err());
+        assert!(solve_pt2("data/day3/test", &Letters, 0).is_err());
+    }
+
+    #[test]
+    fn test_schemes() {
+        let digits = Table::digits();
+        assert_eq!(solve_pt1("data/day3/test_digits", &digits), Ok(2 + 6 + 10));
+        assert_eq!(solve_pt2("data/day3/test_digits", &digits, 3), Ok(3));
+
+        // the rucksacks are not valid UTF-8, they only share the byte 0xff
+        assert_eq!(
+            solve_pt2("data/day3/test_bytes", &priority::Bytes, 2),
+            Ok(255)
+        );
+
+        // digits are no items for letters
+        assert_eq!(
+            solve_pt1("data/day3/test_digits", &Letters),
+            Err(String::from("line 1: invalid item '1'"))
+        );
+    }
 
     #[test]
     fn test_item_priority() {
@@ -114,7 +592,7 @@ mod tests {
     #[test]
     fn test_pt1() {
         // test data and result from the task description
-        assert_eq!(solve_pt1("data/day3/test"), 157);
+        assert_eq!(solve_pt1("data/day3/test", &Letters), Ok(157));
     }
 
     #[test]
@@ -128,6 +606,6 @@ mod tests {
     #[test]
     fn test_pt2() {
         // test data and result from the task description
-        assert_eq!(solve_pt2("data/day3/test"), 70);
+        assert_eq!(solve_pt2("data/day3/test", &Letters, 3), Ok(70));
     }
 }
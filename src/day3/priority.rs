@@ -0,0 +1,213 @@
+// Maps items to priorities and back. Besides the letters of the puzzle there
+// are schemes for custom alphabets, raw bytes and user-defined tables. A table
+// is described by lines of the form `<item> <priority>`, e.g.
+//
+//   a 1
+//   ä 2
+//
+// Empty lines and lines starting with '#' are ignored.
+
+use std::collections::HashMap;
+use std::fs;
+
+use super::itemset::ItemSet;
+use super::{priority_item, try_item_priority};
+
+pub trait PriorityScheme {
+    fn priority(&self, item: char) -> Option<u32>;
+
+    fn item(&self, priority: u32) -> Option<char>;
+
+    // The items of a single line of the input
+    fn decode(&self, line: &[u8]) -> Vec<char> {
+        String::from_utf8_lossy(line)
+            .trim_end_matches('\r')
+            .chars()
+            .collect()
+    }
+}
+
+// a-z have the priorities 1 to 26, A-Z 27 to 52
+pub struct Letters;
+
+impl PriorityScheme for Letters {
+    fn priority(&self, item: char) -> Option<u32> {
+        try_item_priority(item)
+    }
+
+    fn item(&self, priority: u32) -> Option<char> {
+        (1..=52)
+            .contains(&priority)
+            .then(|| priority_item(priority))
+    }
+}
+
+// Every byte is an item, its priority is the byte value
+pub struct Bytes;
+
+impl PriorityScheme for Bytes {
+    fn priority(&self, item: char) -> Option<u32> {
+        let value = item as u32;
+        (value <= 0xff).then_some(value)
+    }
+
+    fn item(&self, priority: u32) -> Option<char> {
+        u8::try_from(priority).ok().map(char::from)
+    }
+
+    fn decode(&self, line: &[u8]) -> Vec<char> {
+        line.iter().map(|byte| char::from(*byte)).collect()
+    }
+}
+
+pub struct Table {
+    priorities: HashMap<char, u32>,
+    items: HashMap<u32, char>,
+}
+
+impl Table {
+    pub fn build(entries: &[(char, u32)]) -> Result<Table, String> {
+        let mut table = Table {
+            priorities: HashMap::new(),
+            items: HashMap::new(),
+        };
+
+        for (item, priority) in entries {
+            if *priority > ItemSet::MAX_PRIORITY {
+                return Err(format!(
+                    "Priority {} of {:?} is larger than {}",
+                    priority,
+                    item,
+                    ItemSet::MAX_PRIORITY
+                ));
+            }
+            if table.priorities.insert(*item, *priority).is_some() {
+                return Err(format!("Item {:?} has several priorities", item));
+            }
+            if table.items.insert(*priority, *item).is_some() {
+                return Err(format!("Priority {} is used by several items", priority));
+            }
+        }
+        Ok(table)
+    }
+
+    // The items of the alphabet have the priorities 1, 2, 3, ...
+    pub fn alphabet(items: &str) -> Result<Table, String> {
+        let entries: Vec<(char, u32)> = items.chars().zip(1..).collect();
+        Table::build(&entries)
+    }
+
+    pub fn digits() -> Table {
+        Table::alphabet("0123456789").unwrap()
+    }
+
+    pub fn parse(text: &str) -> Result<Table, String> {
+        let mut entries = Vec::new();
+        for line in text.lines() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let parts: Vec<&str> = line.split_whitespace().collect();
+            let error = || format!("Expected <item> <priority>: {}", line);
+            if parts.len() != 2 {
+                return Err(error());
+            }
+
+            let mut chars = parts[0].chars();
+            let (Some(item), None) = (chars.next(), chars.next()) else {
+                return Err(error());
+            };
+            let priority = parts[1].parse::<u32>().map_err(|_| error())?;
+            entries.push((item, priority));
+        }
+        Table::build(&entries)
+    }
+
+    pub fn load(file: &str) -> Result<Table, String> {
+        let text = fs::read_to_string(file).map_err(|e| format!("{}: {}", file, e))?;
+        Table::parse(&text)
+    }
+}
+
+impl PriorityScheme for Table {
+    fn priority(&self, item: char) -> Option<u32> {
+        self.priorities.get(&item).copied()
+    }
+
+    fn item(&self, priority: u32) -> Option<char> {
+        self.items.get(&priority).copied()
+    }
+}
+
+// letters, digits, bytes, alphabet:<items> or the file of a priority table
+pub fn build(name: &str) -> Result<Box<dyn PriorityScheme>, String> {
+    match name {
+        "letters" => Ok(Box::new(Letters)),
+        "digits" => Ok(Box::new(Table::digits())),
+        "bytes" => Ok(Box::new(Bytes)),
+        _ => match name.strip_prefix("alphabet:") {
+            Some(items) => Ok(Box::new(Table::alphabet(items)?)),
+            None => Ok(Box::new(Table::load(name)?)),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_letters() {
+        assert_eq!(Letters.priority('p'), Some(16));
+        assert_eq!(Letters.priority('P'), Some(42));
+        assert_eq!(Letters.priority('1'), None);
+        assert_eq!(Letters.item(42), Some('P'));
+        assert_eq!(Letters.item(0), None);
+        assert_eq!(Letters.item(53), None);
+    }
+
+    #[test]
+    fn test_bytes() {
+        let items = Bytes.decode(&[0x00, 0x41, 0xff]);
+        let priorities: Vec<Option<u32>> = items.iter().map(|i| Bytes.priority(*i)).collect();
+        assert_eq!(priorities, vec![Some(0), Some(0x41), Some(0xff)]);
+        assert_eq!(Bytes.item(0x41), Some('A'));
+        assert_eq!(Bytes.priority('€'), None);
+        assert_eq!(Bytes.item(256), None);
+    }
+
+    #[test]
+    fn test_alphabet() {
+        let scheme = Table::alphabet("äöüß").unwrap();
+        assert_eq!(scheme.priority('ü'), Some(3));
+        assert_eq!(scheme.item(4), Some('ß'));
+        assert_eq!(scheme.priority('a'), None);
+        assert_eq!(scheme.decode("öß\r".as_bytes()), vec!['ö', 'ß']);
+
+        assert_eq!(Table::digits().priority('0'), Some(1));
+        assert!(Table::alphabet("abca").is_err());
+    }
+
+    #[test]
+    fn test_parse() {
+        let scheme = Table::parse("# vowels\na 10\n\ne 20\n").unwrap();
+        assert_eq!(scheme.priority('e'), Some(20));
+        assert_eq!(scheme.item(10), Some('a'));
+
+        assert!(Table::parse("a 1\nb 1").is_err());
+        assert!(Table::parse("a 256").is_err());
+        assert!(Table::parse("ab 1").is_err());
+        assert!(Table::parse("a").is_err());
+    }
+
+    #[test]
+    fn test_build() {
+        assert_eq!(build("letters").unwrap().priority('a'), Some(1));
+        assert_eq!(build("digits").unwrap().priority('9'), Some(10));
+        assert_eq!(build("bytes").unwrap().priority('a'), Some(97));
+        assert_eq!(build("alphabet:xyz").unwrap().priority('z'), Some(3));
+        assert!(build("data/day3/missing").is_err());
+    }
+}
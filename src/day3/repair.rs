@@ -0,0 +1,505 @@
+// Plans the repair of rucksacks with item types in both compartments. A swap
+// exchanges two items, either between the compartments of one rucksack or
+// between two rucksacks of a group, so every compartment keeps its size.
+//
+// Within a rucksack every type has to end up in a single compartment. The
+// types kept in the first compartment must fill it exactly, which is a subset
+// sum over the type counts. Every item of such a type found in the second
+// compartment costs one swap, the cheapest subset is found by dynamic
+// programming.
+//
+// In a group, exchanges between rucksacks can be cheaper, e.g. one exchange
+// repairs both `abbc` and `deef`, and some rucksacks like `aaab` can only be
+// repaired this way. The fewest swaps for the whole group are found by an A*
+// search over the number of items of every type in every compartment. Every
+// swap moves two items and of every type in both compartments of a rucksack
+// the smaller part has to move, which bounds the swaps still needed. Repairing
+// each rucksack on its own bounds the search from above.
+//
+// The search visits at most `STATE_LIMIT` states and is skipped for
+// compartments of more than 255 items. If it gives up, the rucksacks are
+// repaired on their own, with the fewest swaps for each of them, and the
+// repair is not marked as minimal.
+
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BinaryHeap, HashMap};
+
+use super::compartments;
+
+// Number of items of a type in the first and the second compartment
+type Counts = BTreeMap<char, (usize, usize)>;
+
+fn type_counts(head: &[char], tail: &[char]) -> Counts {
+    let mut counts = Counts::new();
+    for item in head {
+        counts.entry(*item).or_default().0 += 1;
+    }
+    for item in tail {
+        counts.entry(*item).or_default().1 += 1;
+    }
+    counts
+}
+
+// The types to keep in the first compartment and the number of swaps needed
+fn plan(items: &[char]) -> Option<(Vec<char>, usize)> {
+    let (head, tail) = compartments(items)?;
+    let types: Vec<(char, (usize, usize))> = type_counts(head, tail).into_iter().collect();
+    let size = head.len();
+
+    // cost[i][s]: fewest swaps to fill s places with a subset of the first i
+    // types
+    let mut cost = vec![vec![None; size + 1]; types.len() + 1];
+    cost[0][0] = Some(0);
+    for (i, (_, (in_head, in_tail))) in types.iter().enumerate() {
+        let count = in_head + in_tail;
+        for s in 0..=size {
+            let skip = cost[i][s];
+            let take = (s >= count)
+                .then(|| cost[i][s - count].map(|c| c + in_tail))
+                .flatten();
+            cost[i + 1][s] = match (skip, take) {
+                (Some(a), Some(b)) => Some(a.min(b)),
+                (a, b) => a.or(b),
+            };
+        }
+    }
+    let swaps = cost[types.len()][size]?;
+
+    // walk back through the table to find the chosen types
+    let mut kept = Vec::new();
+    let mut s = size;
+    for i in (0..types.len()).rev() {
+        if cost[i][s] != cost[i + 1][s] {
+            let (item, (in_head, in_tail)) = types[i];
+            kept.push(item);
+            s -= in_head + in_tail;
+        }
+    }
+    Some((kept, swaps))
+}
+
+// Moves the kept types to the first compartment, swapping pairs of misplaced
+// items in the order they occur
+fn apply(items: &[char], kept: &[char]) -> Vec<char> {
+    let size = items.len() / 2;
+    let mut result = items.to_vec();
+
+    let misplaced_head = (0..size).filter(|i| !kept.contains(&items[*i]));
+    let misplaced_tail = (size..items.len()).filter(|i| kept.contains(&items[*i]));
+    for (i, j) in misplaced_head.zip(misplaced_tail) {
+        result.swap(i, j);
+    }
+    result
+}
+
+// Largest number of group states the search may visit
+const STATE_LIMIT: usize = 200_000;
+
+#[derive(Debug)]
+pub struct Repair {
+    pub rucksacks: Vec<Vec<char>>,
+    pub swaps: usize,
+    // false if the search gave up and the swaps are only minimal for every
+    // rucksack on its own
+    pub minimal: bool,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Failure {
+    Impossible,
+    SearchLimit,
+}
+
+impl Failure {
+    pub fn describe(&self) -> String {
+        match self {
+            Failure::Impossible => String::from("can't be repaired"),
+            Failure::SearchLimit => String::from("too large to search for a repair"),
+        }
+    }
+}
+
+// Exchanges an item of type x in compartment c with one of type y in
+// compartment d, as (c, x, d, y)
+type Swap = (usize, usize, usize, usize);
+
+// The numbers of items of every type in every compartment, state[c * types + x]
+// for type x in compartment c. Rucksack r has the compartments 2r and 2r + 1.
+struct Search {
+    types: usize,
+    compartments: usize,
+}
+
+impl Search {
+    // Lower bound of the swaps needed to repair every rucksack
+    fn bound(&self, state: &[u8]) -> usize {
+        let misplaced: usize = state
+            .chunks(2 * self.types)
+            .map(|rucksack| {
+                let (head, tail) = rucksack.split_at(self.types);
+                head.iter()
+                    .zip(tail)
+                    .map(|(h, t)| *h.min(t) as usize)
+                    .sum::<usize>()
+            })
+            .sum();
+        misplaced.div_ceil(2)
+    }
+
+    fn successors(&self, state: &[u8]) -> Vec<(Swap, Vec<u8>)> {
+        let mut result = Vec::new();
+        for c in 0..self.compartments {
+            for d in c + 1..self.compartments {
+                for x in (0..self.types).filter(|x| state[c * self.types + x] > 0) {
+                    for y in (0..self.types).filter(|y| *y != x && state[d * self.types + y] > 0) {
+                        let mut next = state.to_vec();
+                        next[c * self.types + x] -= 1;
+                        next[c * self.types + y] += 1;
+                        next[d * self.types + y] -= 1;
+                        next[d * self.types + x] += 1;
+                        result.push(((c, x, d, y), next));
+                    }
+                }
+            }
+        }
+        result
+    }
+
+    // The fewest swaps repairing every rucksack if there are fewer than
+    // `upper`, None if there aren't
+    fn run(&self, start: Vec<u8>, upper: usize) -> Result<Option<Vec<Swap>>, Failure> {
+        if self.bound(&start) == 0 {
+            return Ok(Some(Vec::new()));
+        }
+
+        // parent, swap from the parent and number of swaps of every state
+        let mut nodes: Vec<(usize, Swap, usize)> = vec![(0, (0, 0, 0, 0), 0)];
+        let mut index = HashMap::from([(start.clone(), 0)]);
+        let mut states = vec![start];
+        // fewest total swaps first, then the deepest state
+        let mut open = BinaryHeap::from([Reverse((self.bound(&states[0]), Reverse(0), 0))]);
+
+        while let Some(Reverse((_, Reverse(swaps), id))) = open.pop() {
+            if swaps > nodes[id].2 {
+                continue;
+            }
+            for (swap, next) in self.successors(&states[id]) {
+                let next_swaps = swaps + 1;
+                let bound = self.bound(&next);
+                if next_swaps + bound >= upper {
+                    continue;
+                }
+
+                let next_id = match index.get(&next) {
+                    Some(&known) if nodes[known].2 <= next_swaps => continue,
+                    Some(&known) => {
+                        nodes[known] = (id, swap, next_swaps);
+                        known
+                    }
+                    None => {
+                        if states.len() == STATE_LIMIT {
+                            return Err(Failure::SearchLimit);
+                        }
+                        nodes.push((id, swap, next_swaps));
+                        index.insert(next.clone(), states.len());
+                        states.push(next);
+                        states.len() - 1
+                    }
+                };
+
+                // the bound never drops by more than one per swap, so the
+                // first repaired state is reached with the fewest swaps
+                if bound == 0 {
+                    let mut path = Vec::new();
+                    let mut current = next_id;
+                    while current != 0 {
+                        path.push(nodes[current].1);
+                        current = nodes[current].0;
+                    }
+                    path.reverse();
+                    return Ok(Some(path));
+                }
+                open.push(Reverse((next_swaps + bound, Reverse(next_swaps), next_id)));
+            }
+        }
+        Ok(None)
+    }
+}
+
+// The repaired group and the fewest swaps for it if there are fewer than
+// `upper`
+fn search_group(group: &[Vec<char>], upper: usize) -> Result<Option<Repair>, Failure> {
+    if group.iter().any(|items| items.len() > 2 * u8::MAX as usize) {
+        return Err(Failure::SearchLimit);
+    }
+
+    let mut types: Vec<char> = group.concat();
+    types.sort_unstable();
+    types.dedup();
+    let type_idx: HashMap<char, usize> = types.iter().enumerate().map(|(i, t)| (*t, i)).collect();
+
+    let search = Search {
+        types: types.len(),
+        compartments: 2 * group.len(),
+    };
+    let mut start = vec![0; search.types * search.compartments];
+    for (r, items) in group.iter().enumerate() {
+        let size = items.len() / 2;
+        for (i, item) in items.iter().enumerate() {
+            let c = 2 * r + usize::from(i >= size);
+            start[c * search.types + type_idx[item]] += 1;
+        }
+    }
+
+    let Some(path) = search.run(start, upper)? else {
+        return Ok(None);
+    };
+
+    // swap the first items of the types in the compartments
+    let mut rucksacks = group.to_vec();
+    let position = |rucksacks: &[Vec<char>], c: usize, item: char| {
+        let size = rucksacks[c / 2].len() / 2;
+        let range = if c.is_multiple_of(2) {
+            0..size
+        } else {
+            size..2 * size
+        };
+        range
+            .into_iter()
+            .find(|i| rucksacks[c / 2][*i] == item)
+            .unwrap()
+    };
+    for &(c, x, d, y) in &path {
+        let i = position(&rucksacks, c, types[x]);
+        let j = position(&rucksacks, d, types[y]);
+        rucksacks[c / 2][i] = types[y];
+        rucksacks[d / 2][j] = types[x];
+    }
+    Ok(Some(Repair {
+        rucksacks,
+        swaps: path.len(),
+        minimal: true,
+    }))
+}
+
+pub fn repair_group(group: &[Vec<char>]) -> Result<Repair, Failure> {
+    if group.iter().any(|items| compartments(items).is_none()) {
+        return Err(Failure::Impossible);
+    }
+
+    // every rucksack on its own
+    let separate = group
+        .iter()
+        .map(|items| plan(items).map(|(kept, swaps)| (apply(items, &kept), swaps)))
+        .collect::<Option<Vec<(Vec<char>, usize)>>>()
+        .map(|plans| Repair {
+            swaps: plans.iter().map(|(_, swaps)| swaps).sum(),
+            rucksacks: plans.into_iter().map(|(items, _)| items).collect(),
+            minimal: true,
+        });
+    if group.len() == 1 {
+        return separate.ok_or(Failure::Impossible);
+    }
+
+    let upper = separate.as_ref().map_or(usize::MAX, |repair| repair.swaps);
+    match search_group(group, upper) {
+        Ok(Some(repair)) => Ok(repair),
+        Ok(None) => separate.ok_or(Failure::Impossible),
+        Err(failure) => separate
+            .map(|repair| Repair {
+                minimal: false,
+                ..repair
+            })
+            .ok_or(failure),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chars(line: &str) -> Vec<char> {
+        line.chars().collect()
+    }
+
+    fn is_repaired(items: &[char]) -> bool {
+        let (head, tail) = compartments(items).unwrap();
+        head.iter().all(|item| !tail.contains(item))
+    }
+
+    fn sorted(rucksacks: &[Vec<char>]) -> Vec<char> {
+        let mut all: Vec<char> = rucksacks.concat();
+        all.sort();
+        all
+    }
+
+    fn repair_rucksack(items: &[char]) -> Option<Repair> {
+        repair_group(&[items.to_vec()]).ok()
+    }
+
+    #[test]
+    fn test_repair_rucksack() {
+        let repair = repair_rucksack(&chars("vJrwpWtwJgWrhcsFMMfFFhFp")).unwrap();
+        assert_eq!(repair.swaps, 1);
+        assert!(is_repaired(&repair.rucksacks[0]));
+
+        let repair = repair_rucksack(&chars("abcdef")).unwrap();
+        assert_eq!(repair.swaps, 0);
+        assert_eq!(repair.rucksacks[0], chars("abcdef"));
+
+        // keeping a and c in front takes one swap, keeping b in front three
+        let repair = repair_rucksack(&chars("abacbcbb")).unwrap();
+        assert_eq!(repair.swaps, 1);
+        assert_eq!(repair.rucksacks[0], chars("acacbbbb"));
+
+        assert!(repair_rucksack(&chars("aaab")).is_none());
+        assert!(repair_rucksack(&chars("abc")).is_none());
+    }
+
+    #[test]
+    fn test_repair_group() {
+        let group = vec![chars("aaab"), chars("cdcd")];
+        let repair = repair_group(&group).unwrap();
+
+        // one exchange between the rucksacks and one swap within cdcd
+        assert_eq!(repair.swaps, 2);
+        assert!(repair.rucksacks.iter().all(|r| is_repaired(r)));
+        assert_eq!(sorted(&repair.rucksacks), sorted(&group));
+
+        assert_eq!(
+            repair_group(&[chars("aaab")]).err(),
+            Some(Failure::Impossible)
+        );
+        assert_eq!(
+            repair_group(&[chars("aaab"), chars("aaaa")]).err(),
+            Some(Failure::Impossible)
+        );
+    }
+
+    // Fewest swaps by a breadth-first search over all arrangements
+    fn brute_force(group: &[Vec<char>]) -> Option<usize> {
+        let positions: Vec<(usize, usize)> = group
+            .iter()
+            .enumerate()
+            .flat_map(|(r, items)| (0..items.len()).map(move |i| (r, i)))
+            .collect();
+        let compartment = |(r, i): (usize, usize)| 2 * r + usize::from(i >= group[r].len() / 2);
+
+        let mut seen = HashMap::from([(group.to_vec(), 0)]);
+        let mut layer = vec![group.to_vec()];
+        for swaps in 0.. {
+            if layer.is_empty() {
+                return None;
+            }
+            if layer
+                .iter()
+                .any(|state| state.iter().all(|items| is_repaired(items)))
+            {
+                return Some(swaps);
+            }
+            let mut next_layer = Vec::new();
+            for state in &layer {
+                for (a, p) in positions.iter().enumerate() {
+                    for q in &positions[a + 1..] {
+                        if compartment(*p) == compartment(*q) {
+                            continue;
+                        }
+                        let mut next = state.clone();
+                        next[p.0][p.1] = state[q.0][q.1];
+                        next[q.0][q.1] = state[p.0][p.1];
+                        if !seen.contains_key(&next) {
+                            seen.insert(next.clone(), swaps + 1);
+                            next_layer.push(next);
+                        }
+                    }
+                }
+            }
+            layer = next_layer;
+        }
+        None
+    }
+
+    #[test]
+    fn test_group_minimal() {
+        for lines in [
+            vec!["abbc", "deef"],
+            vec!["aaab", "cdcd"],
+            vec!["aaaaab", "cdefgh"],
+            vec!["abab", "abab"],
+            vec!["aabbcc", "abca"],
+            vec!["abba", "cc", "dede"],
+            vec!["aaab", "aaab", "cdef"],
+        ] {
+            let group: Vec<Vec<char>> = lines.iter().map(|line| chars(line)).collect();
+            let repair = repair_group(&group).ok();
+            if let Some(repair) = &repair {
+                assert!(repair.minimal);
+                assert!(repair.rucksacks.iter().all(|r| is_repaired(r)));
+                assert_eq!(sorted(&repair.rucksacks), sorted(&group));
+            }
+            assert_eq!(repair.map(|r| r.swaps), brute_force(&group), "{:?}", lines);
+        }
+
+        // one exchange repairs both, on their own they need a swap each
+        let repair = repair_group(&[chars("abbc"), chars("deef")]).unwrap();
+        assert_eq!(repair.swaps, 1);
+    }
+
+    #[test]
+    fn test_large_group() {
+        // two of the 26 a's have to leave the rucksack
+        let broken = format!("{}bcdefghijklmnopqrstuvw", "a".repeat(26));
+        let other = format!("ABCDEFGHIJKLMNOPQRSTUVWX{}", "YZ".repeat(12));
+        let group = vec![chars(&broken), chars(&other)];
+
+        let repair = repair_group(&group).unwrap();
+        assert!(repair.minimal);
+        assert_eq!(repair.swaps, 2);
+        assert!(repair.rucksacks.iter().all(|r| is_repaired(r)));
+        assert_eq!(sorted(&repair.rucksacks), sorted(&group));
+    }
+
+    #[test]
+    fn test_search_limit() {
+        // too many possible exchanges, the rucksacks are repaired on their own
+        let text = std::fs::read_to_string("data/day3/input").unwrap();
+        let group: Vec<Vec<char>> = text.lines().take(6).map(chars).collect();
+        let repair = repair_group(&group).unwrap();
+        assert!(!repair.minimal);
+        assert!(repair.rucksacks.iter().all(|r| is_repaired(r)));
+        let separate: usize = group.iter().map(|items| plan(items).unwrap().1).sum();
+        assert_eq!(repair.swaps, separate);
+    }
+
+    #[test]
+    fn test_minimal() {
+        // compare against all sequences of up to two swaps
+        for line in [
+            "abab", "aabb", "abba", "abcabc", "aabcbc", "abcdab", "abcdcb",
+        ] {
+            let items = chars(line);
+            let n = items.len();
+            let swaps: Vec<(usize, usize)> = (0..n / 2)
+                .flat_map(|i| (n / 2..n).map(move |j| (i, j)))
+                .collect();
+            let after = |sequence: &[&(usize, usize)]| {
+                let mut result = items.clone();
+                for (i, j) in sequence {
+                    result.swap(*i, *j);
+                }
+                is_repaired(&result)
+            };
+
+            let best = if after(&[]) {
+                Some(0)
+            } else if swaps.iter().any(|a| after(&[a])) {
+                Some(1)
+            } else if swaps.iter().any(|a| swaps.iter().any(|b| after(&[a, b]))) {
+                Some(2)
+            } else {
+                None
+            };
+            assert_eq!(repair_rucksack(&items).map(|r| r.swaps), best, "{}", line);
+        }
+    }
+}
@@ -0,0 +1,179 @@
+// Strict checking of the rucksack list. The solutions assume every rucksack
+// and every group share exactly one item, this reports where that is not the
+// case instead of silently using the first shared item.
+
+use super::priority::PriorityScheme;
+use super::{compartments, shared_items};
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum Issue {
+    OddLength(usize),
+    InvalidItem(char),
+    NoSharedItem,
+    SeveralSharedItems(Vec<char>),
+    IncompleteGroup(usize),
+}
+
+impl Issue {
+    pub fn describe(&self) -> String {
+        match self {
+            Issue::OddLength(len) => format!("odd number of items ({})", len),
+            Issue::InvalidItem(item) => format!("invalid item {:?}", item),
+            Issue::NoSharedItem => String::from("no shared item"),
+            Issue::SeveralSharedItems(items) => format!(
+                "several shared items ({})",
+                items.iter().collect::<String>()
+            ),
+            Issue::IncompleteGroup(size) => format!("incomplete group of {}", size),
+        }
+    }
+}
+
+// An issue of a single rucksack or a group, lines are numbered from one
+#[derive(Clone, PartialEq, Debug)]
+pub struct Report {
+    pub first_line: usize,
+    pub last_line: usize,
+    pub issue: Issue,
+}
+
+fn shared_issue(shared: Vec<char>) -> Option<Issue> {
+    match shared.len() {
+        0 => Some(Issue::NoSharedItem),
+        1 => None,
+        _ => Some(Issue::SeveralSharedItems(shared)),
+    }
+}
+
+// The issues of a single rucksack and whether its compartments are valid
+fn check_rucksack(scheme: &dyn PriorityScheme, items: &[char]) -> (Vec<Issue>, bool) {
+    let mut issues = Vec::new();
+
+    let mut invalid: Vec<char> = items
+        .iter()
+        .filter(|item| scheme.priority(**item).is_none())
+        .copied()
+        .collect();
+    invalid.sort_unstable();
+    invalid.dedup();
+    issues.extend(invalid.iter().map(|item| Issue::InvalidItem(*item)));
+
+    let halves = compartments(items);
+    if halves.is_none() {
+        issues.push(Issue::OddLength(items.len()));
+    }
+
+    let Some((head, tail)) = halves.filter(|_| issues.is_empty()) else {
+        return (issues, false);
+    };
+    issues.extend(shared_issue(shared_items(scheme, &[head, tail])));
+    (issues, true)
+}
+
+// Fails for groups of zero rucksacks
+pub fn check(
+    rucksacks: &[Vec<char>],
+    scheme: &dyn PriorityScheme,
+    group_size: usize,
+) -> Result<Vec<Report>, String> {
+    if group_size == 0 {
+        return Err(String::from("Groups need at least one rucksack"));
+    }
+
+    let mut reports = Vec::new();
+    let mut valid = Vec::new();
+
+    for (idx, items) in rucksacks.iter().enumerate() {
+        let (issues, is_valid) = check_rucksack(scheme, items);
+        valid.push(is_valid);
+        reports.extend(issues.into_iter().map(|issue| Report {
+            first_line: idx + 1,
+            last_line: idx + 1,
+            issue,
+        }));
+    }
+
+    for (group_idx, group) in rucksacks.chunks(group_size).enumerate() {
+        let first_line = group_idx * group_size + 1;
+        let last_line = first_line + group.len() - 1;
+
+        let issue = if group.len() < group_size {
+            Some(Issue::IncompleteGroup(group.len()))
+        } else if valid[first_line - 1..last_line].iter().all(|v| *v) {
+            let lists: Vec<&[char]> = group.iter().map(|items| items.as_slice()).collect();
+            shared_issue(shared_items(scheme, &lists))
+        } else {
+            // the invalid rucksacks are already reported
+            None
+        };
+
+        reports.extend(issue.map(|issue| Report {
+            first_line,
+            last_line,
+            issue,
+        }));
+    }
+    Ok(reports)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::priority::Letters;
+
+    fn rucksacks(lines: &[&str]) -> Vec<Vec<char>> {
+        lines.iter().map(|line| line.chars().collect()).collect()
+    }
+
+    #[test]
+    fn test_valid() {
+        let lines = ["abcdeb", "xbyzbz", "bqrsbu"];
+        assert_eq!(check(&rucksacks(&lines), &Letters, 3), Ok(vec![]));
+    }
+
+    #[test]
+    fn test_rucksack_issues() {
+        let lines = ["abcdef", "abab", "abc", "ab1b"];
+        let reports = check(&rucksacks(&lines), &Letters, 2).unwrap();
+
+        let issues: Vec<(usize, Issue)> = reports
+            .into_iter()
+            .filter(|r| r.first_line == r.last_line)
+            .map(|r| (r.first_line, r.issue))
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                (1, Issue::NoSharedItem),
+                (2, Issue::SeveralSharedItems(vec!['a', 'b'])),
+                (3, Issue::OddLength(3)),
+                (4, Issue::InvalidItem('1')),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_group_issues() {
+        let lines = ["bcbd", "cbdb", "axay", "ezez", "zz"];
+        let reports = check(&rucksacks(&lines), &Letters, 2).unwrap();
+
+        let groups: Vec<Report> = reports
+            .into_iter()
+            .filter(|r| r.first_line != r.last_line || r.issue == Issue::IncompleteGroup(1))
+            .collect();
+        assert_eq!(groups.len(), 3);
+        assert_eq!(
+            groups[0].issue,
+            Issue::SeveralSharedItems(vec!['b', 'c', 'd'])
+        );
+        assert_eq!(groups[1].issue, Issue::NoSharedItem);
+        assert_eq!((groups[2].first_line, groups[2].last_line), (5, 5));
+    }
+
+    #[test]
+    fn test_empty_groups() {
+        let lines = ["abcdeb", "xbyzbz", "bqrsbu"];
+        assert!(check(&rucksacks(&lines), &Letters, 0).is_err());
+        assert!(check(&rucksacks(&lines), &Letters, 1).is_ok());
+    }
+}
@@ -0,0 +1,133 @@
+// Analysis of a line with any number of assignments.
+
+use super::interval::IntervalSet;
+use super::{has_overlap, Range};
+
+pub struct GroupAnalysis {
+    // contains[i][j]: assignment i contains assignment j
+    pub contains: Vec<Vec<bool>>,
+    pub overlaps: Vec<Vec<bool>>,
+    // Assignments that can be removed together without losing a section.
+    // Starting with the last one, an assignment is dropped if the ones kept so
+    // far cover it, so of equal assignments the first one is kept.
+    pub redundant: Vec<usize>,
+    // Number of sections covered by any assignment
+    pub coverage: u64,
+}
+
+fn pairwise<F>(ranges: &[Range], relation: F) -> Vec<Vec<bool>>
+where
+    F: Fn(&Range, &Range) -> bool,
+{
+    ranges
+        .iter()
+        .enumerate()
+        .map(|(i, lhs)| {
+            ranges
+                .iter()
+                .enumerate()
+                .map(|(j, rhs)| i != j && relation(lhs, rhs))
+                .collect()
+        })
+        .collect()
+}
+
+pub fn analyze(ranges: &[Range]) -> GroupAnalysis {
+    let coverage = ranges
+        .iter()
+        .copied()
+        .collect::<IntervalSet<u32>>()
+        .covered();
+
+    // from the back, so that the earlier of equal assignments is kept
+    let mut kept = vec![true; ranges.len()];
+    for i in (0..ranges.len()).rev() {
+        let others: IntervalSet<u32> = ranges
+            .iter()
+            .enumerate()
+            .filter(|(j, _)| *j != i && kept[*j])
+            .map(|(_, range)| *range)
+            .collect();
+        kept[i] = others.covered() != coverage;
+    }
+    let redundant = (0..ranges.len()).filter(|i| !kept[*i]).collect();
+
+    GroupAnalysis {
+        contains: pairwise(ranges, |lhs, rhs| lhs.contains(rhs)),
+        overlaps: pairwise(ranges, has_overlap),
+        redundant,
+        coverage,
+    }
+}
+
+// One row per assignment: 'C' contains the other assignment, 'c' is contained
+// in it, 'o' overlaps it and '.' is disjoint
+pub fn matrix_rows(analysis: &GroupAnalysis) -> Vec<String> {
+    let n = analysis.contains.len();
+    (0..n)
+        .map(|i| {
+            (0..n)
+                .map(|j| {
+                    if i == j {
+                        '-'
+                    } else if analysis.contains[i][j] {
+                        'C'
+                    } else if analysis.contains[j][i] {
+                        'c'
+                    } else if analysis.overlaps[i][j] {
+                        'o'
+                    } else {
+                        '.'
+                    }
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_line;
+
+    #[test]
+    fn test_analyze() {
+        let ranges = parse_line("2-8,3-4,6-10,12-12");
+        let analysis = analyze(&ranges);
+
+        assert!(analysis.contains[0][1]);
+        assert!(!analysis.contains[1][0]);
+        assert!(analysis.overlaps[0][2]);
+        assert!(!analysis.overlaps[1][2]);
+        assert!(!analysis.overlaps[3][3]);
+
+        assert_eq!(analysis.redundant, vec![1]);
+        assert_eq!(analysis.coverage, 10);
+        assert_eq!(matrix_rows(&analysis), vec!["-Co.", "c-..", "o.-.", "...-"]);
+    }
+
+    #[test]
+    fn test_redundant_by_union() {
+        // 4-6 is covered by the other two together
+        let analysis = analyze(&parse_line("2-5,4-6,6-9"));
+        assert_eq!(analysis.redundant, vec![1]);
+
+        // only one of equal assignments can be dropped
+        let analysis = analyze(&parse_line("2-5,2-5"));
+        assert_eq!(analysis.redundant, vec![1]);
+        let analysis = analyze(&parse_line("2-5,1-3,2-5,2-5"));
+        assert_eq!(analysis.redundant, vec![2, 3]);
+        let analysis = analyze(&parse_line("2-5,2-5,1-6"));
+        assert_eq!(analysis.redundant, vec![0, 1]);
+
+        // each of the halves is covered, but not both by the rest
+        let analysis = analyze(&parse_line("1-4,1-2,3-4"));
+        assert_eq!(analysis.redundant, vec![1, 2]);
+        let analysis = analyze(&parse_line("1-2,3-4,1-4"));
+        assert_eq!(analysis.redundant, vec![2]);
+
+        let analysis = analyze(&parse_line("2-5"));
+        assert_eq!(analysis.redundant, vec![]);
+        assert_eq!(analysis.coverage, 4);
+    }
+}
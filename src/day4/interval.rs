@@ -0,0 +1,305 @@
+// Closed intervals of sections and sets of them. An interval with start > end
+// contains no section and is considered empty.
+
+pub trait Bound: Copy + Ord {
+    const MIN: Self;
+    const MAX: Self;
+    fn succ(self) -> Option<Self>;
+    fn pred(self) -> Option<Self>;
+    // Number of steps from self to other, other must not be smaller
+    fn distance(self, other: Self) -> u64;
+}
+
+macro_rules! impl_bound {
+    ($($t:ty),*) => {
+        $(
+            impl Bound for $t {
+                const MIN: Self = <$t>::MIN;
+                const MAX: Self = <$t>::MAX;
+
+                fn succ(self) -> Option<Self> {
+                    self.checked_add(1)
+                }
+
+                fn pred(self) -> Option<Self> {
+                    self.checked_sub(1)
+                }
+
+                fn distance(self, other: Self) -> u64 {
+                    (other as i128 - self as i128) as u64
+                }
+            }
+        )*
+    };
+}
+
+impl_bound!(u32, u64, i32, i64);
+
+// Ordered by start, then by end
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: Bound> Interval<T> {
+    pub fn new(start: T, end: T) -> Interval<T> {
+        Interval { start, end }
+    }
+
+    // An interval without sections
+    pub fn empty() -> Interval<T> {
+        Interval::new(T::MAX, T::MIN)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.start > self.end
+    }
+
+    // Number of sections, saturating for the full range of 64-bit bounds
+    pub fn length(&self) -> u64 {
+        if self.is_empty() {
+            0
+        } else {
+            self.start.distance(self.end).saturating_add(1)
+        }
+    }
+
+    pub fn contains(&self, other: &Interval<T>) -> bool {
+        other.difference(self).is_empty()
+    }
+
+    pub fn intersection(&self, other: &Interval<T>) -> Interval<T> {
+        Interval::new(self.start.max(other.start), self.end.min(other.end))
+    }
+
+    // No section in between, but no common section either
+    pub fn is_adjacent(&self, other: &Interval<T>) -> bool {
+        !self.is_empty()
+            && !other.is_empty()
+            && (self.end.succ() == Some(other.start) || other.end.succ() == Some(self.start))
+    }
+
+    // The union if it is an interval again, i.e. both overlap or are adjacent
+    pub fn union(&self, other: &Interval<T>) -> Option<Interval<T>> {
+        if other.is_empty() {
+            return Some(*self);
+        }
+        if self.is_empty() {
+            return Some(*other);
+        }
+        if self.intersection(other).is_empty() && !self.is_adjacent(other) {
+            return None;
+        }
+        Some(Interval::new(
+            self.start.min(other.start),
+            self.end.max(other.end),
+        ))
+    }
+
+    // The non-empty parts of self not covered by other
+    pub fn difference(&self, other: &Interval<T>) -> Vec<Interval<T>> {
+        if self.is_empty() {
+            return Vec::new();
+        }
+        let common = self.intersection(other);
+        if common.is_empty() {
+            return vec![*self];
+        }
+
+        let mut parts = Vec::new();
+        if let Some(before) = common.start.pred().filter(|_| common.start > self.start) {
+            parts.push(Interval::new(self.start, before));
+        }
+        if let Some(after) = common.end.succ().filter(|_| common.end < self.end) {
+            parts.push(Interval::new(after, self.end));
+        }
+        parts
+    }
+}
+
+// Disjoint intervals, adjacent and overlapping intervals are merged
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: Bound> IntervalSet<T> {
+    pub fn new() -> IntervalSet<T> {
+        IntervalSet {
+            intervals: Vec::new(),
+        }
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+
+        // the intervals are sorted and disjoint, so the ones overlapping or
+        // adjacent to the new interval form a consecutive run, found by binary
+        // search
+        let first = self.intervals.partition_point(|existing| {
+            existing.end < interval.start && !existing.is_adjacent(&interval)
+        });
+        let last = self.intervals.partition_point(|existing| {
+            existing.start <= interval.end || existing.is_adjacent(&interval)
+        });
+
+        let merged = self.intervals[first..last]
+            .iter()
+            .fold(interval, |merged, existing| {
+                merged
+                    .union(existing)
+                    .expect("Expected touching intervals!")
+            });
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    // Appends an interval which does not start before any interval of the
+    // set, merging it with the last one if they touch. Cheaper than insert for
+    // intervals arriving in order.
+    pub fn push(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+        if let Some(last) = self.intervals.last_mut() {
+            assert!(last.start <= interval.start, "Expected ordered intervals!");
+            if let Some(union) = last.union(&interval) {
+                *last = union;
+                return;
+            }
+        }
+        self.intervals.push(interval);
+    }
+
+    // The intervals in increasing order
+    pub fn iter(&self) -> std::slice::Iter<'_, Interval<T>> {
+        self.intervals.iter()
+    }
+
+    // Number of sections covered by any interval
+    pub fn covered(&self) -> u64 {
+        self.intervals.iter().map(|i| i.length()).sum()
+    }
+}
+
+impl<T: Bound> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> IntervalSet<T> {
+        let mut set = IntervalSet::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+        set
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn iv(start: i64, end: i64) -> Interval<i64> {
+        Interval::new(start, end)
+    }
+
+    #[test]
+    fn test_basics() {
+        assert_eq!(iv(2, 4).length(), 3);
+        assert_eq!(iv(4, 2).length(), 0);
+        assert!(iv(4, 2).is_empty());
+        assert!(!iv(4, 4).is_empty());
+        assert!(Interval::<u32>::empty().is_empty());
+        assert_eq!(Interval::<i64>::empty().length(), 0);
+        assert_eq!(Interval::new(i64::MIN, i64::MAX).length(), u64::MAX);
+        assert_eq!(Interval::new(0u32, u32::MAX).length(), 1 << 32);
+
+        assert!(iv(2, 8).contains(&iv(3, 7)));
+        assert!(iv(2, 8).contains(&iv(2, 8)));
+        assert!(!iv(2, 8).contains(&iv(1, 7)));
+        assert!(iv(2, 8).contains(&iv(5, 4)));
+    }
+
+    #[test]
+    fn test_intersection_union() {
+        assert_eq!(iv(2, 6).intersection(&iv(4, 8)), iv(4, 6));
+        assert!(iv(2, 4).intersection(&iv(5, 8)).is_empty());
+
+        assert!(iv(2, 4).is_adjacent(&iv(5, 8)));
+        assert!(iv(5, 8).is_adjacent(&iv(2, 4)));
+        assert!(!iv(2, 4).is_adjacent(&iv(4, 8)));
+        assert!(!iv(2, 4).is_adjacent(&iv(6, 8)));
+
+        assert_eq!(iv(2, 4).union(&iv(5, 8)), Some(iv(2, 8)));
+        assert_eq!(iv(2, 6).union(&iv(4, 5)), Some(iv(2, 6)));
+        assert_eq!(iv(2, 4).union(&iv(6, 8)), None);
+        assert_eq!(iv(2, 4).union(&iv(9, 1)), Some(iv(2, 4)));
+    }
+
+    #[test]
+    fn test_difference() {
+        assert_eq!(iv(2, 8).difference(&iv(4, 5)), vec![iv(2, 3), iv(6, 8)]);
+        assert_eq!(iv(2, 8).difference(&iv(0, 5)), vec![iv(6, 8)]);
+        assert_eq!(iv(2, 8).difference(&iv(9, 10)), vec![iv(2, 8)]);
+        assert_eq!(iv(2, 8).difference(&iv(1, 9)), vec![]);
+        assert_eq!(
+            Interval::new(0u32, 3).difference(&Interval::new(0, 0)),
+            vec![Interval::new(1, 3)]
+        );
+    }
+
+    #[test]
+    fn test_ordering() {
+        let mut intervals = vec![iv(3, 4), iv(1, 9), iv(1, 2)];
+        intervals.sort();
+        assert_eq!(intervals, vec![iv(1, 2), iv(1, 9), iv(3, 4)]);
+    }
+
+    #[test]
+    fn test_interval_set() {
+        let set: IntervalSet<i64> = [iv(10, 12), iv(1, 3), iv(5, 6), iv(4, 4), iv(20, 19)]
+            .into_iter()
+            .collect();
+        assert_eq!(set, IntervalSet::from_iter([iv(1, 6), iv(10, 12)]));
+        assert_eq!(set.covered(), 9);
+        assert_eq!(set.iter().count(), 2);
+
+        let mut set = set;
+        set.insert(iv(0, 11));
+        assert_eq!(set, IntervalSet::from_iter([iv(0, 12)]));
+        assert_eq!(set.covered(), 13);
+        assert_eq!(IntervalSet::<i64>::new().covered(), 0);
+    }
+
+    #[test]
+    fn test_interval_set_insert() {
+        let mut set: IntervalSet<i64> = [iv(1, 2), iv(6, 7), iv(10, 12), iv(20, 22)]
+            .into_iter()
+            .collect();
+        // merges only the neighbours it touches, adjacent ones included
+        set.insert(iv(3, 9));
+        assert_eq!(set, IntervalSet::from_iter([iv(1, 12), iv(20, 22)]));
+        set.insert(iv(14, 15));
+        set.insert(iv(-5, -3));
+        set.insert(iv(30, 30));
+        let expected = [iv(-5, -3), iv(1, 12), iv(14, 15), iv(20, 22), iv(30, 30)];
+        assert_eq!(set.iter().copied().collect::<Vec<_>>(), expected);
+        set.insert(iv(13, 13));
+        assert_eq!(set.iter().nth(1), Some(&iv(1, 15)));
+
+        let mut set = IntervalSet::new();
+        set.insert(Interval::new(u32::MAX, u32::MAX));
+        set.insert(Interval::new(0, u32::MAX - 1));
+        assert_eq!(set.covered(), 1 << 32);
+        assert_eq!(set.iter().count(), 1);
+
+        let mut pushed = IntervalSet::new();
+        for interval in [iv(1, 3), iv(4, 5), iv(5, 9), iv(11, 12), iv(13, 12)] {
+            pushed.push(interval);
+        }
+        assert_eq!(pushed, IntervalSet::from_iter([iv(1, 9), iv(11, 12)]));
+
+        // many inserts in increasing order stay cheap
+        let set: IntervalSet<i64> = (0..100_000).map(|i| iv(2 * i, 2 * i)).collect();
+        assert_eq!(set.iter().count(), 100_000);
+    }
+}
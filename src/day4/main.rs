@@ -1,46 +1,48 @@
 use aoc::read_lines;
+use std::env;
 
-struct Range {
-    start: u32,
-    end: u32,
-}
+mod group;
+mod interval;
+mod optimize;
+mod parse;
+mod sweep;
+mod tree;
 
-impl Range {
-    fn contains(&self, r: &Range) -> bool {
-        return self.start <= r.start && self.end >= r.end;
-    }
+use interval::{Bound, Interval, IntervalSet};
+use parse::{Options, ParseError};
+use std::str::FromStr;
 
-    fn build(substr: &str) -> Result<Range, &'static str> {
-        let mut number_it = substr.split('-');
-        let first = number_it.next().ok_or("invalid range")?;
-        let second = number_it.next().ok_or("invalid range")?;
+type Range = Interval<u32>;
 
-        Ok(Range {
-            start: first.parse::<u32>().unwrap(),
-            end: second.parse::<u32>().unwrap(),
-        })
+impl Range {
+    fn build(substr: &str) -> Result<Range, ParseError> {
+        parse::parse_range(substr, 0, Options::default())
     }
 }
 
-fn is_contained(lhs: &Range, rhs: &Range) -> bool {
-    return lhs.contains(rhs) || rhs.contains(lhs);
+fn is_contained<T: Bound>(lhs: &Interval<T>, rhs: &Interval<T>) -> bool {
+    lhs.contains(rhs) || rhs.contains(lhs)
 }
 
-fn has_overlap(lhs: &Range, rhs: &Range) -> bool {
-    return (lhs.start >= rhs.start && lhs.start <= rhs.end)
-        || (rhs.start >= lhs.start && rhs.start <= lhs.end);
+// At least one section is in both ranges
+fn has_overlap<T: Bound>(lhs: &Interval<T>, rhs: &Interval<T>) -> bool {
+    !lhs.intersection(rhs).is_empty()
 }
 
 fn parse_line(line: &str) -> Vec<Range> {
-    let mut result = Vec::new();
+    parse::parse_line(line, Options::default())
+        .unwrap_or_else(|err| panic!("Expected valid range! {}", err.describe()))
+}
 
-    for rstr in line.split(',') {
-        let range = Range::build(rstr).expect("Expected valid range!");
-        result.push(range)
-    }
-    result
+fn any_pair<T, F>(ranges: &[Interval<T>], decision_fn: &F) -> bool
+where
+    F: Fn(&Interval<T>, &Interval<T>) -> bool,
+{
+    (0..ranges.len()).any(|i| (i + 1..ranges.len()).any(|j| decision_fn(&ranges[i], &ranges[j])))
 }
 
+// Number of lines with at least one pair of assignments for which the
+// decision holds
 fn solve<F>(file: &str, decision_fn: F) -> u32
 where
     F: Fn(&Range, &Range) -> bool,
@@ -48,22 +50,251 @@ where
     let mut total_score = 0;
 
     let lines = read_lines(file).unwrap();
-    for rline in lines {
-        if let Ok(line) = rline {
-            let ranges = parse_line(line.as_str());
-            assert_eq!(ranges.len(), 2);
+    for line in lines.map_while(Result::ok) {
+        let ranges = parse_line(line.as_str());
+        total_score += if any_pair(&ranges, &decision_fn) {
+            1
+        } else {
+            0
+        };
+    }
+    total_score
+}
+
+// Validates every line and solves both parts with bounds of type T. Returns
+// the number of invalid lines.
+fn check<T>(file: &str, options: Options) -> usize
+where
+    T: Bound + FromStr,
+{
+    let mut invalid = 0;
+    let mut contained = 0;
+    let mut overlapping = 0;
+
+    let lines = read_lines(file).unwrap();
+    for (idx, line) in lines.map_while(Result::ok).enumerate() {
+        match parse::parse_line::<T>(line.as_str(), options) {
+            Ok(mut ranges) => {
+                // removed assignments have no sections to compare
+                ranges.retain(|range| !range.is_empty());
+                contained += any_pair(&ranges, &is_contained) as u32;
+                overlapping += any_pair(&ranges, &has_overlap) as u32;
+            }
+            Err(err) => {
+                println!("line {}, {}", idx + 1, err.describe());
+                invalid += 1;
+            }
+        }
+    }
+
+    println!("{} invalid lines", invalid);
+    println!("Lines with a contained assignment: {}", contained);
+    println!("Lines with overlapping assignments: {}", overlapping);
+    invalid
+}
 
-            total_score += if decision_fn(&ranges[0], &ranges[1]) {
-                1
+fn print_groups(file: &str) {
+    let mut total_redundant = 0;
+
+    let lines = read_lines(file).unwrap();
+    for (idx, line) in lines.map_while(Result::ok).enumerate() {
+        let analysis = group::analyze(&parse_line(line.as_str()));
+        total_redundant += analysis.redundant.len();
+
+        println!(
+            "line {}: {} assignments, {} redundant, {} sections covered",
+            idx + 1,
+            analysis.contains.len(),
+            analysis.redundant.len(),
+            analysis.coverage
+        );
+        for row in group::matrix_rows(&analysis) {
+            println!("    {}", row);
+        }
+    }
+    println!("{} redundant assignments in total", total_redundant);
+}
+
+fn format_sections(set: &IntervalSet<u32>) -> String {
+    let parts: Vec<String> = set
+        .iter()
+        .map(|r| {
+            if r.start == r.end {
+                r.start.to_string()
             } else {
-                0
-            };
+                format!("{}-{}", r.start, r.end)
+            }
+        })
+        .collect();
+    if parts.is_empty() {
+        String::from("none")
+    } else {
+        parts.join(",")
+    }
+}
+
+fn print_sweep(file: &str) {
+    let ranges = read_lines(file)
+        .unwrap()
+        .map_while(Result::ok)
+        .flat_map(|line| parse_line(line.as_str()));
+    let result = sweep::sweep(ranges);
+
+    let Some(span) = result.span else {
+        println!("No assignments");
+        return;
+    };
+    println!("Sections {} to {}", span.start, span.end);
+    println!(
+        "At most {} elves at sections {}",
+        result.max_coverage,
+        format_sections(&result.max_at)
+    );
+    println!("Covered by nobody: {}", format_sections(&result.uncovered));
+
+    println!("Elves Sections");
+    for (count, sections) in result.histogram.iter().enumerate() {
+        if *sections == 0 {
+            continue;
         }
+        println!("{:>5} {:>8}", count, sections);
     }
-    total_score
+}
+
+fn print_query(file: &str, query: &str) {
+    let query = if query.contains('-') {
+        Range::build(query).expect("Expected valid range!")
+    } else {
+        let section = query.parse::<u32>().expect("Expected section!");
+        Range::new(section, section)
+    };
+
+    // the line and the position within the line of every assignment
+    let mut ranges = Vec::new();
+    let mut origins = Vec::new();
+    let lines = read_lines(file).unwrap();
+    for (line_idx, line) in lines.map_while(Result::ok).enumerate() {
+        for (pos, range) in parse_line(line.as_str()).into_iter().enumerate() {
+            ranges.push(range);
+            origins.push((line_idx + 1, pos + 1));
+        }
+    }
+
+    let index = tree::IntervalTree::build(&ranges);
+    let found = if query.start == query.end {
+        index.covering(query.start)
+    } else {
+        index.overlapping(&query)
+    };
+
+    for idx in &found {
+        let (line, pos) = origins[*idx];
+        println!(
+            "line {}, elf {}: {}-{}",
+            line, pos, ranges[*idx].start, ranges[*idx].end
+        );
+    }
+    println!("{} assignments found", found.len());
+}
+
+// Prints the trimmed assignment list, either so that the elves of every line
+// don't overlap or so that no two elves of any line overlap. Removed
+// assignments are written as '-', which `check --removed` reads back.
+fn print_trim(file: &str, global: bool) {
+    let lines: Vec<Vec<Range>> = read_lines(file)
+        .unwrap()
+        .map_while(Result::ok)
+        .map(|line| parse_line(line.as_str()))
+        .collect();
+
+    let trimmed: Vec<Vec<Option<Range>>> = if global {
+        let all: Vec<Range> = lines.concat();
+        let mut result = optimize::trim(&all).into_iter();
+        lines
+            .iter()
+            .map(|ranges| result.by_ref().take(ranges.len()).collect())
+            .collect()
+    } else {
+        lines.iter().map(|ranges| optimize::trim(ranges)).collect()
+    };
+
+    let mut total = 0;
+    for (ranges, result) in lines.iter().zip(&trimmed) {
+        println!("{}", optimize::format_line(result));
+        total += optimize::trimmed(ranges, result);
+    }
+    // on stderr to keep the output a valid assignment list
+    eprintln!("{} sections trimmed", total);
 }
 
 fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() > 2 && args[1] == "query" {
+        // day4 query <section|start-end> [file]
+        let file = args.get(3).map_or("data/day4/input", |s| s.as_str());
+
+        print_query(file, &args[2]);
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "check" {
+        // day4 check [--normalize] [--removed] [--bounds u32|u64|i32|i64] [file]
+        let mut options = Options::default();
+        let mut bounds = "u32";
+        let mut file = "data/day4/input";
+
+        let mut it = args[2..].iter();
+        while let Some(arg) = it.next() {
+            match arg.as_str() {
+                "--normalize" => options.normalize = true,
+                "--removed" => options.removed = true,
+                "--bounds" => bounds = it.next().expect("Expected bound type!"),
+                _ => file = arg,
+            }
+        }
+
+        let invalid = match bounds {
+            "u32" => check::<u32>(file, options),
+            "u64" => check::<u64>(file, options),
+            "i32" => check::<i32>(file, options),
+            "i64" => check::<i64>(file, options),
+            _ => panic!("Unknown bound type {}!", bounds),
+        };
+        if invalid > 0 {
+            std::process::exit(1);
+        }
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "trim" {
+        // day4 trim [--global] [file]
+        let global = args.iter().any(|arg| arg == "--global");
+        let file = args[2..]
+            .iter()
+            .find(|arg| *arg != "--global")
+            .map_or("data/day4/input", |s| s.as_str());
+
+        print_trim(file, global);
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "sweep" {
+        // day4 sweep [file], all assignments of all lines
+        let file = args.get(2).map_or("data/day4/input", |s| s.as_str());
+
+        print_sweep(file);
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "groups" {
+        // day4 groups [file], lines may contain any number of assignments
+        let file = args.get(2).map_or("data/day4/input", |s| s.as_str());
+
+        print_groups(file);
+        return;
+    }
+
     let solution_pt1 = solve("data/day4/input", is_contained);
     println!("The solution to part one is {}", solution_pt1);
 
@@ -93,6 +324,38 @@ mod tests_day4 {
         assert!(!has_overlap(&r1, &r3));
     }
 
+    #[test]
+    fn test_extreme_bounds() {
+        let full = Interval::new(0, u64::MAX);
+        assert!(has_overlap(
+            &Interval::new(0, u64::MAX - 1),
+            &Interval::new(0, 0)
+        ));
+        assert!(has_overlap(&full, &Interval::new(u64::MAX, u64::MAX)));
+        assert!(!has_overlap(
+            &Interval::new(0, u64::MAX - 1),
+            &Interval::new(u64::MAX, u64::MAX)
+        ));
+        assert!(is_contained(&full, &Interval::new(0, 0)));
+
+        let full = Interval::new(i64::MIN, i64::MAX);
+        assert!(has_overlap(&full, &Interval::new(i64::MIN, i64::MIN)));
+        assert!(has_overlap(&full, &full));
+        assert!(!has_overlap(
+            &Interval::new(i64::MIN, -1),
+            &Interval::new(0, i64::MAX)
+        ));
+
+        assert_eq!(
+            check::<u64>("data/day4/test_extremes", Options::default()),
+            1
+        );
+        assert_eq!(
+            check::<i64>("data/day4/test_extremes", Options::default()),
+            2
+        );
+    }
+
     #[test]
     fn test_parse_line() {
         let line = "2-4,12-42";
@@ -114,4 +377,31 @@ mod tests_day4 {
         assert_eq!(solve("data/day4/test", is_contained), 2);
         assert_eq!(solve("data/day4/test", has_overlap), 4);
     }
+
+    #[test]
+    fn test_build() {
+        assert_eq!(Range::build("12-42"), Ok(Range::new(12, 42)));
+        assert!(Range::build("42-12").is_err());
+        assert!(Range::build("12").is_err());
+        assert!(Range::build("12-x").is_err());
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(check::<u32>("data/day4/test", Options::default()), 0);
+        // a negative, a reversed and a 64-bit range
+        assert_eq!(check::<u32>("data/day4/test_signed", Options::default()), 3);
+        assert_eq!(check::<i64>("data/day4/test_signed", Options::default()), 1);
+        let options = Options {
+            normalize: true,
+            ..Options::default()
+        };
+        assert_eq!(check::<i64>("data/day4/test_signed", options), 0);
+    }
+
+    #[test]
+    fn test_groups() {
+        assert_eq!(solve("data/day4/test_groups", is_contained), 2);
+        assert_eq!(solve("data/day4/test_groups", has_overlap), 2);
+    }
 }
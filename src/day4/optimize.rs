@@ -0,0 +1,177 @@
+// Trims assignments so that no two of them overlap, removing as few sections
+// as possible. Assignments can only shrink and have to stay contiguous, an
+// assignment may lose all of its sections.
+//
+// The sections are handed out from left to right. An elf keeps its sections
+// until its assignment ends, then the unused elf covering the next section
+// with the largest end takes over. An elf only loses its turn once its
+// assignment has ended, so every section covered by anyone is kept and no
+// solution keeps more.
+
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+
+use super::Range;
+
+// The trimmed assignments, None if an assignment is removed completely
+pub fn trim(ranges: &[Range]) -> Vec<Option<Range>> {
+    let mut order: Vec<usize> = (0..ranges.len())
+        .filter(|i| !ranges[*i].is_empty())
+        .collect();
+    order.sort_by_key(|i| ranges[*i].start);
+
+    let mut result = vec![None; ranges.len()];
+    // elves whose assignment started, by largest end and then lowest index
+    let mut available: BinaryHeap<(u32, Reverse<usize>)> = BinaryHeap::new();
+    let mut next = 0;
+    // first section not yet handed out, may be past the largest section
+    let mut cursor: u64 = 0;
+
+    loop {
+        while next < order.len() && ranges[order[next]].start as u64 <= cursor {
+            let idx = order[next];
+            available.push((ranges[idx].end, Reverse(idx)));
+            next += 1;
+        }
+
+        match available.pop() {
+            Some((end, Reverse(idx))) if end as u64 >= cursor => {
+                result[idx] = Some(Range::new(cursor as u32, end));
+                cursor = end as u64 + 1;
+            }
+            // all remaining elves have ended before the cursor
+            _ => {
+                available.clear();
+                if next == order.len() {
+                    break;
+                }
+                cursor = ranges[order[next]].start as u64;
+            }
+        }
+    }
+    result
+}
+
+// Number of sections removed by the trimming
+pub fn trimmed(ranges: &[Range], trimmed: &[Option<Range>]) -> u64 {
+    let before: u64 = ranges.iter().map(|r| r.length()).sum();
+    let after: u64 = trimmed.iter().flatten().map(|r| r.length()).sum();
+    before - after
+}
+
+// The assignments in the `a-b,c-d` format, removed assignments as '-' which
+// parses as an assignment without sections with `Options::removed`
+pub fn format_line(trimmed: &[Option<Range>]) -> String {
+    let parts: Vec<String> = trimmed
+        .iter()
+        .map(|range| match range {
+            Some(r) => format!("{}-{}", r.start, r.end),
+            None => String::from("-"),
+        })
+        .collect();
+    parts.join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::interval::IntervalSet;
+    use crate::parse::{self, Options};
+    use crate::{any_pair, check, has_overlap, parse_line};
+    use std::fs;
+
+    fn check_valid(ranges: &[Range], result: &[Option<Range>]) {
+        for (range, trimmed) in ranges.iter().zip(result) {
+            if let Some(t) = trimmed {
+                assert!(range.contains(t));
+            }
+        }
+        let kept: Vec<Range> = result.iter().flatten().copied().collect();
+        for i in 0..kept.len() {
+            for j in i + 1..kept.len() {
+                assert!(!has_overlap(&kept[i], &kept[j]));
+            }
+        }
+
+        // nothing covered by anyone is lost
+        let union: IntervalSet<u32> = ranges.iter().copied().collect();
+        let removed = trimmed(ranges, result);
+        let total: u64 = ranges.iter().map(|r| r.length()).sum();
+        assert_eq!(total - removed, union.covered());
+    }
+
+    #[test]
+    fn test_pairs() {
+        let ranges = parse_line("5-7,7-9");
+        let result = trim(&ranges);
+        assert_eq!(format_line(&result), "5-7,8-9");
+        assert_eq!(trimmed(&ranges, &result), 1);
+
+        let ranges = parse_line("2-8,3-7");
+        let result = trim(&ranges);
+        assert_eq!(format_line(&result), "2-8,-");
+        assert_eq!(trimmed(&ranges, &result), 5);
+
+        let ranges = parse_line("6-6,4-6");
+        assert_eq!(format_line(&trim(&ranges)), "-,4-6");
+
+        let ranges = parse_line("2-4,6-8");
+        assert_eq!(format_line(&trim(&ranges)), "2-4,6-8");
+    }
+
+    #[test]
+    fn test_many() {
+        let text = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8\n1-3,0-0,3-4";
+        let ranges: Vec<Range> = text.lines().flat_map(parse_line).collect();
+        let result = trim(&ranges);
+        check_valid(&ranges, &result);
+
+        let ranges = parse_line(&format!(
+            "1-10,3-4,9-{},{}-{}",
+            u32::MAX,
+            u32::MAX,
+            u32::MAX
+        ));
+        let result = trim(&ranges);
+        check_valid(&ranges, &result);
+        assert_eq!(result[3], None);
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let removed = Options {
+            removed: true,
+            ..Options::default()
+        };
+        let text = fs::read_to_string("data/day4/test").unwrap();
+        let mut output = Vec::new();
+        for line in text.lines() {
+            let ranges = parse_line(line);
+            let result = trim(&ranges);
+            let formatted = format_line(&result);
+
+            // removed assignments parse as empty ones
+            let parsed = parse::parse_line::<u32>(&formatted, removed).unwrap();
+            assert_eq!(parsed.len(), ranges.len());
+            for (range, trimmed) in parsed.iter().zip(&result) {
+                match trimmed {
+                    Some(t) => assert_eq!(range, t),
+                    None => assert!(range.is_empty()),
+                }
+            }
+            assert!(!any_pair(&parsed, &has_overlap));
+            // trimming again changes nothing
+            assert_eq!(format_line(&trim(&parsed)), formatted);
+            output.push(formatted);
+        }
+
+        // the strict parser rejects removed assignments
+        let file = std::env::temp_dir().join("day4_trim_round_trip");
+        fs::write(&file, output.join("\n")).unwrap();
+        let invalid = check::<u32>(file.to_str().unwrap(), Options::default());
+        let invalid_removed = check::<u32>(file.to_str().unwrap(), removed);
+        fs::remove_file(&file).unwrap();
+        assert!(invalid > 0);
+        assert_eq!(invalid_removed, 0);
+    }
+}
@@ -0,0 +1,193 @@
+// Strict parsing of assignments like `2-4` or `-8--3`. A '-' at the start of
+// a bound is its sign, the separator is the first '-' after the first
+// character. With `Options::removed`, a single '-' is an assignment without
+// sections, as written for removed assignments by `trim`. Errors carry the column in the line and the
+// offending text.
+
+use std::str::FromStr;
+
+use super::interval::{Bound, Interval};
+
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum ErrorKind {
+    MissingSeparator,
+    InvalidBound,
+    Reversed,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub struct ParseError {
+    // column of the offending text in the line, starting at one
+    pub column: usize,
+    pub text: String,
+    pub kind: ErrorKind,
+}
+
+impl ParseError {
+    pub fn describe(&self) -> String {
+        let what = match self.kind {
+            ErrorKind::MissingSeparator => "expected <start>-<end>",
+            ErrorKind::InvalidBound => "invalid section",
+            ErrorKind::Reversed => "start after end",
+        };
+        format!("column {}: {} '{}'", self.column, what, self.text)
+    }
+}
+
+#[derive(Clone, Copy, Default)]
+pub struct Options {
+    // swap the bounds of reversed ranges instead of rejecting them
+    pub normalize: bool,
+    // accept '-' for a removed assignment, to read the output of `trim` back
+    pub removed: bool,
+}
+
+fn parse_bound<T: FromStr>(text: &str, offset: usize) -> Result<T, ParseError> {
+    text.parse::<T>().map_err(|_| ParseError {
+        column: offset + 1,
+        text: text.to_string(),
+        kind: ErrorKind::InvalidBound,
+    })
+}
+
+// Parses a single range starting at byte `offset` of its line
+pub fn parse_range<T>(
+    text: &str,
+    offset: usize,
+    options: Options,
+) -> Result<Interval<T>, ParseError>
+where
+    T: Bound + FromStr,
+{
+    if options.removed && text == "-" {
+        return Ok(Interval::empty());
+    }
+
+    let separator = text
+        .char_indices()
+        .skip(1)
+        .find(|(_, c)| *c == '-')
+        .map(|(idx, _)| idx)
+        .ok_or_else(|| ParseError {
+            column: offset + 1,
+            text: text.to_string(),
+            kind: ErrorKind::MissingSeparator,
+        })?;
+
+    let start = parse_bound(&text[..separator], offset)?;
+    let end = parse_bound(&text[separator + 1..], offset + separator + 1)?;
+
+    if start <= end {
+        Ok(Interval::new(start, end))
+    } else if options.normalize {
+        Ok(Interval::new(end, start))
+    } else {
+        Err(ParseError {
+            column: offset + 1,
+            text: text.to_string(),
+            kind: ErrorKind::Reversed,
+        })
+    }
+}
+
+pub fn parse_line<T>(line: &str, options: Options) -> Result<Vec<Interval<T>>, ParseError>
+where
+    T: Bound + FromStr,
+{
+    let mut result = Vec::new();
+    let mut offset = 0;
+
+    for text in line.split(',') {
+        result.push(parse_range(text, offset, options)?);
+        offset += text.len() + 1;
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const STRICT: Options = Options {
+        normalize: false,
+        removed: false,
+    };
+
+    #[test]
+    fn test_bounds() {
+        let ranges = parse_line::<i64>("-8--3,-2-5,7-9", STRICT).unwrap();
+        assert_eq!(
+            ranges,
+            vec![
+                Interval::new(-8, -3),
+                Interval::new(-2, 5),
+                Interval::new(7, 9)
+            ]
+        );
+
+        let ranges = parse_line::<u64>("4294967296-18446744073709551615", STRICT).unwrap();
+        assert_eq!(ranges[0], Interval::new(1 << 32, u64::MAX));
+
+        assert!(parse_line::<u32>("4294967296-4294967297", STRICT).is_err());
+        assert!(parse_line::<u32>("-3-2", STRICT).is_err());
+    }
+
+    #[test]
+    fn test_errors() {
+        let error = parse_line::<u32>("2-4,6-x8", STRICT).unwrap_err();
+        assert_eq!(
+            error,
+            ParseError {
+                column: 7,
+                text: String::from("x8"),
+                kind: ErrorKind::InvalidBound,
+            }
+        );
+        assert_eq!(error.describe(), "column 7: invalid section 'x8'");
+
+        let error = parse_line::<u32>("2-4,68", STRICT).unwrap_err();
+        assert_eq!((error.column, error.kind), (5, ErrorKind::MissingSeparator));
+
+        let error = parse_line::<u32>("2-4,,", STRICT).unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (5, ""));
+
+        let error = parse_line::<u32>("2-4, 6-8", STRICT).unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (5, " 6"));
+
+        let error = parse_line::<u32>("2-4,--", STRICT).unwrap_err();
+        assert_eq!((error.column, error.kind), (5, ErrorKind::InvalidBound));
+
+        let error = parse_line::<u32>("2-4-5", STRICT).unwrap_err();
+        assert_eq!((error.column, error.text.as_str()), (3, "4-5"));
+    }
+
+    #[test]
+    fn test_removed() {
+        let error = parse_line::<u32>("2-4,-", STRICT).unwrap_err();
+        assert_eq!((error.column, error.kind), (5, ErrorKind::MissingSeparator));
+
+        let options = Options {
+            removed: true,
+            ..STRICT
+        };
+        let ranges = parse_line::<u32>("-,4-6,-", options).unwrap();
+        assert!(ranges[0].is_empty() && ranges[2].is_empty());
+        assert_eq!(ranges[1], Interval::new(4, 6));
+    }
+
+    #[test]
+    fn test_reversed() {
+        let error = parse_line::<u32>("2-4,8-6", STRICT).unwrap_err();
+        assert_eq!(
+            (error.column, error.text.as_str(), error.kind),
+            (5, "8-6", ErrorKind::Reversed)
+        );
+
+        let options = Options {
+            normalize: true,
+            ..STRICT
+        };
+        let ranges = parse_line::<i32>("8-6,-1--5", options).unwrap();
+        assert_eq!(ranges, vec![Interval::new(6, 8), Interval::new(-5, -1)]);
+    }
+}
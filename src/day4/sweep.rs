@@ -0,0 +1,124 @@
+// Treats all assignments as one timeline. Only the start and end points of the
+// assignments are stored, so the analysis does not depend on the number of
+// sections in between. The segments between the points arrive in order, so
+// they are appended to the result sets instead of inserted.
+
+use std::collections::BTreeMap;
+
+use super::interval::{Interval, IntervalSet};
+use super::Range;
+
+pub struct Sweep {
+    // from the first to the last section of any assignment
+    pub span: Option<Range>,
+    pub max_coverage: usize,
+    pub max_at: IntervalSet<u32>,
+    // sections in the span covered by nobody
+    pub uncovered: IntervalSet<u32>,
+    // histogram[k]: number of sections in the span covered by exactly k
+    // assignments
+    pub histogram: Vec<u64>,
+}
+
+pub fn sweep<I>(ranges: I) -> Sweep
+where
+    I: IntoIterator<Item = Range>,
+{
+    // change of the coverage at every section, the end is exclusive
+    let mut events: BTreeMap<u64, i64> = BTreeMap::new();
+    for range in ranges.into_iter().filter(|r| !r.is_empty()) {
+        *events.entry(range.start as u64).or_default() += 1;
+        *events.entry(range.end as u64 + 1).or_default() -= 1;
+    }
+
+    let mut result = Sweep {
+        span: None,
+        max_coverage: 0,
+        max_at: IntervalSet::new(),
+        uncovered: IntervalSet::new(),
+        histogram: Vec::new(),
+    };
+
+    let (Some(first), Some(last)) = (events.keys().next(), events.keys().last()) else {
+        return result;
+    };
+    result.span = Some(Interval::new(*first as u32, (*last - 1) as u32));
+
+    let mut coverage: i64 = 0;
+    let positions: Vec<(u64, i64)> = events.into_iter().collect();
+    for pair in positions.windows(2) {
+        let ((start, delta), (next, _)) = (pair[0], pair[1]);
+        coverage += delta;
+
+        let count = coverage as usize;
+        let segment = Interval::new(start as u32, (next - 1) as u32);
+        if result.histogram.len() <= count {
+            result.histogram.resize(count + 1, 0);
+        }
+        result.histogram[count] += segment.length();
+
+        if count == 0 {
+            result.uncovered.push(segment);
+        }
+        if count > result.max_coverage {
+            result.max_coverage = count;
+            result.max_at = IntervalSet::new();
+        }
+        if count == result.max_coverage {
+            result.max_at.push(segment);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parse_line;
+
+    fn ranges(text: &str) -> Vec<Range> {
+        text.lines().flat_map(parse_line).collect()
+    }
+
+    #[test]
+    fn test_sweep() {
+        let text = "2-4,6-8\n2-3,4-5\n5-7,7-9\n2-8,3-7\n6-6,4-6\n2-6,4-8";
+        let result = sweep(ranges(text));
+
+        assert_eq!(result.span, Some(Interval::new(2, 9)));
+        assert_eq!(result.max_coverage, 8);
+        assert_eq!(result.max_at, IntervalSet::from_iter([Interval::new(6, 6)]));
+        assert_eq!(result.uncovered, IntervalSet::new());
+        assert_eq!(result.histogram, vec![0, 1, 0, 0, 2, 1, 1, 2, 1]);
+    }
+
+    #[test]
+    fn test_gaps() {
+        let result = sweep(ranges("1-2,8-9\n5-5,9-9"));
+
+        assert_eq!(result.span, Some(Interval::new(1, 9)));
+        assert_eq!(
+            result.uncovered,
+            IntervalSet::from_iter([Interval::new(3, 4), Interval::new(6, 7)])
+        );
+        assert_eq!(result.max_coverage, 2);
+        assert_eq!(result.max_at, IntervalSet::from_iter([Interval::new(9, 9)]));
+        assert_eq!(result.histogram, vec![4, 4, 1]);
+    }
+
+    #[test]
+    fn test_edge_cases() {
+        let result = sweep(Vec::new());
+        assert_eq!(result.span, None);
+        assert!(result.histogram.is_empty());
+
+        // an end at the largest section and touching assignments
+        let result = sweep(ranges(&format!("1-3,4-{}", u32::MAX)));
+        assert_eq!(result.span, Some(Interval::new(1, u32::MAX)));
+        assert_eq!(result.max_coverage, 1);
+        assert_eq!(
+            result.max_at,
+            IntervalSet::from_iter([Interval::new(1, u32::MAX)])
+        );
+    }
+}
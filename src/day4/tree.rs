@@ -0,0 +1,148 @@
+// A static interval tree over the assignments. The ranges are sorted by their
+// start, the middle of every slice is the root of the subtree of that slice
+// and additionally stores the largest end within the slice. Subtrees ending
+// before the query or starting after it are skipped, so a query only visits
+// O(log n) entries per result instead of scanning all of them.
+
+use super::Range;
+
+pub struct IntervalTree {
+    // ranges with their index in the input, sorted by start
+    entries: Vec<(Range, usize)>,
+    // max_end[i]: largest end of the subtree rooted at entry i
+    max_end: Vec<u32>,
+}
+
+impl IntervalTree {
+    pub fn build(ranges: &[Range]) -> IntervalTree {
+        let mut entries: Vec<(Range, usize)> = ranges
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|(_, range)| !range.is_empty())
+            .map(|(idx, range)| (range, idx))
+            .collect();
+        entries.sort();
+
+        let mut tree = IntervalTree {
+            max_end: vec![0; entries.len()],
+            entries,
+        };
+        tree.build_subtree(0, tree.entries.len());
+        tree
+    }
+
+    fn build_subtree(&mut self, lo: usize, hi: usize) -> Option<u32> {
+        if lo >= hi {
+            return None;
+        }
+        let mid = (lo + hi) / 2;
+        let left = self.build_subtree(lo, mid);
+        let right = self.build_subtree(mid + 1, hi);
+
+        let max_end = [left, right]
+            .into_iter()
+            .flatten()
+            .fold(self.entries[mid].0.end, u32::max);
+        self.max_end[mid] = max_end;
+        Some(max_end)
+    }
+
+    fn collect(&self, lo: usize, hi: usize, query: &Range, result: &mut Vec<usize>) {
+        if lo >= hi {
+            return;
+        }
+        let mid = (lo + hi) / 2;
+        if self.max_end[mid] < query.start {
+            return;
+        }
+
+        self.collect(lo, mid, query, result);
+
+        let (range, idx) = &self.entries[mid];
+        if range.start > query.end {
+            // all ranges to the right start even later
+            return;
+        }
+        if range.end >= query.start {
+            result.push(*idx);
+        }
+        self.collect(mid + 1, hi, query, result);
+    }
+
+    // Indices of the ranges sharing at least one section with the query
+    pub fn overlapping(&self, query: &Range) -> Vec<usize> {
+        let mut result = Vec::new();
+        if !query.is_empty() {
+            self.collect(0, self.entries.len(), query, &mut result);
+        }
+        result.sort();
+        result
+    }
+
+    // Indices of the ranges containing the section
+    pub fn covering(&self, section: u32) -> Vec<usize> {
+        self.overlapping(&Range::new(section, section))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::has_overlap;
+
+    // a simple linear congruential generator for reproducible test data
+    fn random_ranges(count: usize, seed: u64) -> Vec<Range> {
+        let mut state = seed;
+        let mut next = |limit: u64| {
+            state = state
+                .wrapping_mul(6364136223846793005)
+                .wrapping_add(1442695040888963407);
+            ((state >> 33) % limit) as u32
+        };
+        (0..count)
+            .map(|_| {
+                let start = next(1000);
+                Range::new(start, start + next(50))
+            })
+            .collect()
+    }
+
+    fn naive(ranges: &[Range], query: &Range) -> Vec<usize> {
+        (0..ranges.len())
+            .filter(|i| has_overlap(&ranges[*i], query))
+            .collect()
+    }
+
+    #[test]
+    fn test_against_naive() {
+        let ranges = random_ranges(2000, 42);
+        let tree = IntervalTree::build(&ranges);
+
+        for query in random_ranges(200, 7) {
+            assert_eq!(tree.overlapping(&query), naive(&ranges, &query));
+        }
+        for section in [0, 1, 500, 999, 1048, 5000] {
+            let query = Range::new(section, section);
+            assert_eq!(tree.covering(section), naive(&ranges, &query));
+        }
+    }
+
+    #[test]
+    fn test_small() {
+        let ranges = [
+            Range::new(2, 4),
+            Range::new(6, 8),
+            Range::new(3, 7),
+            Range::new(9, 1),
+        ];
+        let tree = IntervalTree::build(&ranges);
+
+        assert_eq!(tree.covering(4), vec![0, 2]);
+        assert_eq!(tree.covering(5), vec![2]);
+        assert_eq!(tree.covering(9), vec![]);
+        assert_eq!(tree.overlapping(&Range::new(8, 20)), vec![1]);
+        assert_eq!(tree.overlapping(&Range::new(5, 4)), vec![]);
+        assert_eq!(IntervalTree::build(&[]).covering(1), vec![]);
+    }
+}
@@ -0,0 +1,188 @@
+// Parses the drawing of the stacks. The columns are taken from the numbered
+// footer line instead of a fixed stride, so there can be any number of
+// stacks and labels of any width. A crate belongs to the stack whose number
+// is below it. Tabs are expanded to the next multiple of four columns, the
+// stride of the drawing in the puzzle, and trailing whitespace is ignored.
+
+use super::Crate;
+
+const TAB_WIDTH: usize = 4;
+
+fn expand_tabs(line: &str) -> Vec<char> {
+    let mut chars = Vec::new();
+    for c in line.chars() {
+        if c == '\t' {
+            chars.push(' ');
+            while !chars.len().is_multiple_of(TAB_WIDTH) {
+                chars.push(' ');
+            }
+        } else {
+            chars.push(c);
+        }
+    }
+    chars
+}
+
+// Runs of non-whitespace characters with their first and last column
+fn tokens(chars: &[char]) -> Vec<(usize, usize)> {
+    let mut result = Vec::new();
+    let mut start = None;
+    for (idx, c) in chars.iter().enumerate() {
+        match (start, c.is_whitespace()) {
+            (None, false) => start = Some(idx),
+            (Some(s), true) => {
+                result.push((s, idx - 1));
+                start = None;
+            }
+            _ => {}
+        }
+    }
+    if let Some(s) = start {
+        result.push((s, chars.len() - 1));
+    }
+    result
+}
+
+// First and last column of every stack number, the numbers have to be 1 to n
+fn footer_columns(line: &str) -> Result<Vec<(usize, usize)>, String> {
+    let chars = expand_tabs(line);
+    let columns = tokens(&chars);
+
+    for (idx, (start, end)) in columns.iter().enumerate() {
+        let text: String = chars[*start..=*end].iter().collect();
+        if text.parse::<usize>() != Ok(idx + 1) {
+            return Err(format!(
+                "column {}: expected stack number {}, found '{}'",
+                start + 1,
+                idx + 1,
+                text
+            ));
+        }
+    }
+    if columns.is_empty() {
+        return Err(String::from("no stack numbers"));
+    }
+    Ok(columns)
+}
+
+pub fn parse(text: &str) -> Result<Vec<Vec<Crate>>, String> {
+    let lines: Vec<&str> = text.lines().collect();
+    let footer_idx = lines
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .ok_or_else(|| String::from("empty drawing"))?;
+
+    let columns = footer_columns(lines[footer_idx])
+        .map_err(|err| format!("line {}, {}", footer_idx + 1, err))?;
+    let mut stacks = vec![Vec::new(); columns.len()];
+
+    // from the bottom up so that the crates can be pushed
+    for (line_idx, line) in lines[..footer_idx].iter().enumerate().rev() {
+        let chars = expand_tabs(line);
+        let mut used = vec![false; columns.len()];
+
+        for (start, end) in tokens(&chars) {
+            let text: String = chars[start..=end].iter().collect();
+            let error = |what: &str| {
+                format!(
+                    "line {}, column {}: {} '{}'",
+                    line_idx + 1,
+                    start + 1,
+                    what,
+                    text
+                )
+            };
+
+            if end - start < 2
+                || chars[start] != '['
+                || chars[end] != ']'
+                || chars[start + 1..end].iter().any(|c| *c == '[' || *c == ']')
+            {
+                return Err(error("expected a crate like [A]"));
+            }
+            let label: String = chars[start + 1..end].iter().collect();
+            let below: Vec<usize> = (0..columns.len())
+                .filter(|i| columns[*i].0 <= end && start <= columns[*i].1)
+                .collect();
+            let &[stack] = below.as_slice() else {
+                return Err(error("not above exactly one stack number"));
+            };
+            if used[stack] {
+                return Err(error("second crate above the same stack"));
+            }
+            used[stack] = true;
+
+            // a crate needs a crate below it
+            if stacks[stack].len() + 1 != footer_idx - line_idx {
+                return Err(error("floating crate"));
+            }
+            stacks[stack].push(Crate::build(&label));
+        }
+    }
+    Ok(stacks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn labels(stacks: &[Vec<Crate>]) -> Vec<Vec<&str>> {
+        stacks
+            .iter()
+            .map(|s| s.iter().map(|c| c.label.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_footer() {
+        assert_eq!(
+            footer_columns(" 1   2   3 "),
+            Ok(vec![(1, 1), (5, 5), (9, 9)])
+        );
+        assert_eq!(footer_columns("1\t2 3"), Ok(vec![(0, 0), (4, 4), (6, 6)]));
+        assert!(footer_columns(" 1 21 42    5  60 ").is_err());
+        assert!(footer_columns(" 1   3 ").is_err());
+        assert!(footer_columns("   ").is_err());
+    }
+
+    #[test]
+    fn test_wide() {
+        let text = "\
+[Ab]                                       [X]
+[C]         [DEF]                      [Y] [Z]
+ 1   2   3   4   5   6   7   8   9   10  11  12";
+        let stacks = parse(text).unwrap();
+        assert_eq!(stacks.len(), 12);
+        assert_eq!(labels(&stacks)[0], vec!["C", "Ab"]);
+        assert_eq!(labels(&stacks)[3], vec!["DEF"]);
+        assert_eq!(labels(&stacks)[10], vec!["Y"]);
+        assert_eq!(labels(&stacks)[11], vec!["Z", "X"]);
+    }
+
+    #[test]
+    fn test_tabs_and_whitespace() {
+        let text = "\t[D]\n[N]\t[C]   \t  \n[Z] [M]\t[P]\n 1   2   3\t\n\n";
+        let stacks = parse(text).unwrap();
+        assert_eq!(
+            labels(&stacks),
+            vec![vec!["Z", "N"], vec!["M", "C", "D"], vec!["P"]]
+        );
+    }
+
+    #[test]
+    fn test_errors() {
+        let err = parse("[A] [B]\n 1 ").unwrap_err();
+        assert_eq!(
+            err,
+            "line 1, column 5: not above exactly one stack number '[B]'"
+        );
+        assert_eq!(
+            parse("[A]\n   \n 1 ").unwrap_err(),
+            "line 1, column 1: floating crate '[A]'"
+        );
+        assert!(parse(" A \n 1 ").is_err());
+        assert!(parse("[]\n 1 ").is_err());
+        assert!(parse("[A][B]\n 1  2").is_err());
+        assert!(parse("").is_err());
+    }
+}
@@ -1,55 +1,71 @@
+use std::env;
 use std::fs;
 
-#[derive(Clone, Copy, Debug)]
+mod drawing;
+mod render;
+
+#[derive(Clone, PartialEq, Debug)]
 struct Crate {
-    name: char,
+    label: String,
 }
 
 impl Crate {
-    fn build(text: &[char]) -> Crate {
-        Crate { name: text[1] }
+    fn build(label: &str) -> Crate {
+        Crate {
+            label: label.to_string(),
+        }
     }
 }
 
-fn parse_num_stacks(line: &str) -> usize {
-    line.split(' ')
-        .map(|s| s.parse::<usize>())
-        .filter(|i| i.is_ok())
-        .count()
-}
-
 fn parse_stacks(text: &str) -> Vec<Vec<Crate>> {
-    let lines: Vec<&str> = text.lines().collect();
-
-    let last_line = lines.last().unwrap();
-
-    let num_stacks = parse_num_stacks(last_line);
-    let mut stacks = Vec::new();
-
-    for _ in 0..num_stacks {
-        stacks.push(Vec::new());
-    }
+    drawing::parse(text).unwrap_or_else(|err| panic!("Expected valid drawing! {}", err))
+}
 
-    for line in lines {
-        let chars: Vec<char> = line.chars().collect();
+#[derive(Clone, Copy)]
+enum MoveType {
+    Pt1,
+    Pt2,
+}
 
-        for i in 0..num_stacks {
-            let start_idx = i * 4;
-            let end_idx = (i + 1) * 4 - 1;
+#[derive(Clone, PartialEq, Debug)]
+enum Issue {
+    Malformed,
+    // stack numbers start at one
+    UnknownStack(usize),
+    NotEnoughCrates { stack: usize, available: usize },
+}
 
-            if end_idx <= chars.len() && chars[start_idx] == '[' {
-                stacks[i].insert(0, Crate::build(&chars[start_idx..end_idx]));
+impl Issue {
+    fn describe(&self) -> String {
+        match self {
+            Issue::Malformed => String::from("expected 'move <n> from <stack> to <stack>'"),
+            Issue::UnknownStack(stack) => format!("no stack {}", stack),
+            Issue::NotEnoughCrates { stack, available } => {
+                format!("stack {} has only {} crates", stack, available)
             }
         }
     }
+}
 
-    stacks
+// A move that could not be applied, with the stacks before the move
+#[derive(Clone, PartialEq, Debug)]
+struct MoveError {
+    // line of the move, starting at one
+    line: usize,
+    text: String,
+    issue: Issue,
+    stacks: Vec<Vec<Crate>>,
 }
 
-#[derive(Clone, Copy)]
-enum MoveType {
-    Pt1,
-    Pt2,
+impl MoveError {
+    fn describe(&self) -> String {
+        format!(
+            "line {}: {} '{}'",
+            self.line,
+            self.issue.describe(),
+            self.text
+        )
+    }
 }
 
 struct Move {
@@ -60,72 +76,95 @@ struct Move {
 }
 
 impl Move {
-    pub fn build(line: &str, kind: MoveType) -> Move {
-        let mut it = line.split(' ');
-        it.next().unwrap(); // move
-        let num: usize = it.next().unwrap().parse().unwrap();
-        it.next().unwrap(); // from
-        let src: usize = it.next().unwrap().parse().unwrap();
-        it.next().unwrap(); // to
-        let dst: usize = it.next().unwrap().parse().unwrap();
-
-        // assumes that the index is one and not zero-based
-        // so subtract one for src and dst
-        Move {
-            src: src - 1,
-            dst: dst - 1,
-            num: num,
-            kind: kind,
-        }
+    pub fn build(line: &str, kind: MoveType) -> Result<Move, Issue> {
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let ["move", num, "from", src, "to", dst] = words.as_slice() else {
+            return Err(Issue::Malformed);
+        };
+        let number = |text: &str| text.parse::<usize>().map_err(|_| Issue::Malformed);
+        let (num, src, dst) = (number(num)?, number(src)?, number(dst)?);
+
+        // the stacks are numbered from one, so subtract one for src and dst
+        Ok(Move {
+            src: src.checked_sub(1).ok_or(Issue::UnknownStack(src))?,
+            dst: dst.checked_sub(1).ok_or(Issue::UnknownStack(dst))?,
+            num,
+            kind,
+        })
     }
 
-    fn apply_pt1(&self, stacks: &mut Vec<Vec<Crate>>) {
-        for _ in 0..self.num {
-            let c = stacks[self.src].pop();
-            if let Some(c) = c {
-                stacks[self.dst].push(c);
-            }
-        }
+    fn apply_pt1(&self, stacks: &mut [Vec<Crate>], moved: Vec<Crate>) {
+        stacks[self.dst].extend(moved.into_iter().rev());
+    }
+
+    fn apply_pt2(&self, stacks: &mut [Vec<Crate>], moved: Vec<Crate>) {
+        stacks[self.dst].extend(moved);
     }
 
-    fn apply_pt2(&self, stacks: &mut Vec<Vec<Crate>>) {
-        let mut temp: Vec<Crate> = Vec::new();
-        for _ in 0..self.num {
-            let c = stacks[self.src].pop();
-            if let Some(c) = c {
-                temp.push(c);
+    // Leaves the stacks unchanged if the move is not possible
+    pub fn apply(&self, stacks: &mut [Vec<Crate>]) -> Result<(), Issue> {
+        for idx in [self.src, self.dst] {
+            if idx >= stacks.len() {
+                return Err(Issue::UnknownStack(idx + 1));
             }
         }
-
-        for c in temp.into_iter().rev() {
-            stacks[self.dst].push(c.clone());
+        let available = stacks[self.src].len();
+        if available < self.num {
+            return Err(Issue::NotEnoughCrates {
+                stack: self.src + 1,
+                available,
+            });
         }
-    }
 
-    pub fn apply(&self, stacks: &mut Vec<Vec<Crate>>) {
+        let moved = stacks[self.src].split_off(available - self.num);
         match self.kind {
             MoveType::Pt1 => {
-                self.apply_pt1(stacks);
+                self.apply_pt1(stacks, moved);
             }
             MoveType::Pt2 => {
-                self.apply_pt2(stacks);
+                self.apply_pt2(stacks, moved);
             }
         }
+        Ok(())
     }
 }
 
-fn apply_moves(stacks: &mut Vec<Vec<Crate>>, text: &str, kind: MoveType) {
-    for line in text.lines() {
-        let mov = Move::build(line, kind.clone());
-        mov.apply(stacks);
+// Applies the moves of the text and returns the invalid ones. Stops at the
+// first invalid move, unless lenient, then it is skipped and reported with the
+// others.
+fn apply_moves(
+    stacks: &mut [Vec<Crate>],
+    text: &str,
+    kind: MoveType,
+    lenient: bool,
+) -> Vec<MoveError> {
+    let mut errors = Vec::new();
+
+    for (idx, line) in text.lines().enumerate() {
+        if line.trim().is_empty() {
+            continue;
+        }
+        let result = Move::build(line, kind).and_then(|mov| mov.apply(stacks));
+        if let Err(issue) = result {
+            errors.push(MoveError {
+                line: idx + 1,
+                text: line.to_string(),
+                issue,
+                stacks: stacks.to_vec(),
+            });
+            if !lenient {
+                break;
+            }
+        }
     }
+    errors
 }
 
-fn get_top_crates(stacks: &Vec<Vec<Crate>>) -> String {
+fn get_top_crates(stacks: &[Vec<Crate>]) -> String {
     stacks
         .iter()
         .filter_map(|s| s.last())
-        .map(|c| c.name)
+        .map(|c| c.label.as_str())
         .collect::<String>()
 }
 
@@ -136,18 +175,85 @@ fn split_file(file_str: &str) -> (&str, &str) {
     (stack_str, move_str)
 }
 
-fn solve(file: &str, kind: MoveType) -> String {
+// The stacks after all moves of the file and the moves that could not be
+// applied, in strict mode at most one
+fn final_stacks(file: &str, kind: MoveType, lenient: bool) -> (Vec<Vec<Crate>>, Vec<MoveError>) {
     let file_str = fs::read_to_string(file).expect("Unable to read file");
     let (stack_str, move_str) = split_file(file_str.as_str());
 
     let mut stacks = parse_stacks(stack_str);
 
-    apply_moves(&mut stacks, move_str, kind);
+    let mut errors = apply_moves(&mut stacks, move_str, kind, lenient);
+    // the moves start after the drawing and the empty line
+    let offset = stack_str.lines().count() + 1;
+    for err in &mut errors {
+        err.line += offset;
+    }
+
+    (stacks, errors)
+}
 
+fn solve(file: &str, kind: MoveType) -> String {
+    let (stacks, errors) = final_stacks(file, kind, false);
+    if let Some(err) = errors.first() {
+        panic!("Expected valid move! {}", err.describe());
+    }
     get_top_crates(&stacks)
 }
 
+// Applies the moves of both parts and prints every invalid move with the
+// stacks it was applied to. Returns the number of invalid moves.
+fn check(file: &str, lenient: bool) -> usize {
+    let mut invalid = 0;
+
+    for (part, kind) in [(1, MoveType::Pt1), (2, MoveType::Pt2)] {
+        let (stacks, errors) = final_stacks(file, kind, lenient);
+        for err in &errors {
+            println!("part {}, {}", part, err.describe());
+            println!("{}", render::render(&err.stacks));
+        }
+        if errors.is_empty() || lenient {
+            println!("Top crates of part {}: {}", part, get_top_crates(&stacks));
+        }
+        invalid += errors.len();
+    }
+    invalid
+}
+
 fn main() {
+    let args: Vec<String> = env::args().collect();
+
+    if args.len() > 1 && args[1] == "show" {
+        // day5 show [1|2] [file], draws the stacks after all moves
+        let kind = match args.get(2).map(|s| s.as_str()) {
+            None | Some("1") => MoveType::Pt1,
+            Some("2") => MoveType::Pt2,
+            Some(other) => panic!("Unknown part {}!", other),
+        };
+        let file = args.get(3).map_or("data/day5/input", |s| s.as_str());
+
+        let (stacks, errors) = final_stacks(file, kind, false);
+        if let Some(err) = errors.first() {
+            panic!("Expected valid move! {}", err.describe());
+        }
+        println!("{}", render::render(&stacks));
+        return;
+    }
+
+    if args.len() > 1 && args[1] == "check" {
+        // day5 check [--lenient] [file], lenient skips invalid moves
+        let lenient = args.iter().any(|arg| arg == "--lenient");
+        let file = args[2..]
+            .iter()
+            .find(|arg| *arg != "--lenient")
+            .map_or("data/day5/input", |s| s.as_str());
+
+        if check(file, lenient) > 0 {
+            std::process::exit(1);
+        }
+        return;
+    }
+
     let solution_pt1 = solve("data/day5/input", MoveType::Pt1);
     println!("The solution to part one is {}", solution_pt1);
 
@@ -159,12 +265,6 @@ fn main() {
 mod test {
     use super::*;
 
-    #[test]
-    fn test_parse_num_stacks() {
-        let text = " 1 21 42    5  60 ";
-        assert_eq!(parse_num_stacks(&text), 5);
-    }
-
     #[test]
     fn test_parse_stacks() {
         let text = "[D]        \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ";
@@ -177,9 +277,10 @@ mod test {
     }
 
     fn create_test_data() -> (String, Vec<Vec<Crate>>) {
-        let mut stacks = Vec::new();
-        stacks.push(vec![Crate { name: 'A' }, Crate { name: 'B' }]);
-        stacks.push(vec![Crate { name: 'X' }]);
+        let stacks = vec![
+            vec![Crate::build("A"), Crate::build("B")],
+            vec![Crate::build("X")],
+        ];
 
         let move_text =
             String::from("move 1 from 2 to 1\nmove 3 from 1 to 2\nmove 1 from 2 to 1\n");
@@ -189,26 +290,26 @@ mod test {
     #[test]
     fn test_apply_moves_pt1() {
         let (text, mut stacks) = create_test_data();
-        apply_moves(&mut stacks, text.as_str(), MoveType::Pt1);
+        assert!(apply_moves(&mut stacks, text.as_str(), MoveType::Pt1, false).is_empty());
 
         assert_eq!(stacks[0].len(), 1);
-        assert_eq!(stacks[0][0].name, 'A');
+        assert_eq!(stacks[0][0].label, "A");
         assert_eq!(stacks[1].len(), 2);
-        assert_eq!(stacks[1][0].name, 'X');
-        assert_eq!(stacks[1][1].name, 'B');
+        assert_eq!(stacks[1][0].label, "X");
+        assert_eq!(stacks[1][1].label, "B");
     }
 
     #[test]
     fn test_apply_moves_pt2() {
         let (text, mut stacks) = create_test_data();
 
-        apply_moves(&mut stacks, text.as_str(), MoveType::Pt2);
+        assert!(apply_moves(&mut stacks, text.as_str(), MoveType::Pt2, false).is_empty());
 
         assert_eq!(stacks[0].len(), 1);
-        assert_eq!(stacks[0][0].name, 'X');
+        assert_eq!(stacks[0][0].label, "X");
         assert_eq!(stacks[1].len(), 2);
-        assert_eq!(stacks[1][0].name, 'A');
-        assert_eq!(stacks[1][1].name, 'B');
+        assert_eq!(stacks[1][0].label, "A");
+        assert_eq!(stacks[1][1].label, "B");
     }
 
     #[test]
@@ -227,10 +328,10 @@ mod test {
         let move_lines = move_str.lines().collect::<Vec<&str>>();
         assert_eq!(move_lines.len(), 4);
 
-        apply_moves(&mut stacks, &move_lines[0], MoveType::Pt1);
-        apply_moves(&mut stacks, &move_lines[1], MoveType::Pt1);
-        apply_moves(&mut stacks, &move_lines[2], MoveType::Pt1);
-        apply_moves(&mut stacks, &move_lines[3], MoveType::Pt1);
+        assert!(apply_moves(&mut stacks, move_lines[0], MoveType::Pt1, false).is_empty());
+        assert!(apply_moves(&mut stacks, move_lines[1], MoveType::Pt1, false).is_empty());
+        assert!(apply_moves(&mut stacks, move_lines[2], MoveType::Pt1, false).is_empty());
+        assert!(apply_moves(&mut stacks, move_lines[3], MoveType::Pt1, false).is_empty());
 
         let solution = get_top_crates(&stacks);
 
@@ -239,4 +340,75 @@ mod test {
 
         assert_eq!(solve("data/day5/test", MoveType::Pt2), String::from("MCD"));
     }
+
+    #[test]
+    fn test_final_stacks() {
+        let (stacks, errors) = final_stacks("data/day5/test", MoveType::Pt1, false);
+        assert!(errors.is_empty());
+        assert_eq!(
+            render::render(&stacks),
+            "        [Z]\n        [N]\n        [D]\n[C] [M] [P]\n 1   2   3 "
+        );
+    }
+
+    #[test]
+    fn test_build() {
+        assert!(Move::build("move 3 from 1 to 2", MoveType::Pt1).is_ok());
+        assert!(Move::build("move  3 from 1 to 2 ", MoveType::Pt1).is_ok());
+        for line in [
+            "move 3 from 1",
+            "move x from 1 to 2",
+            "move 3 from -1 to 2",
+            "take 3 from 1 to 2",
+        ] {
+            assert_eq!(
+                Move::build(line, MoveType::Pt1).err(),
+                Some(Issue::Malformed)
+            );
+        }
+        assert_eq!(
+            Move::build("move 3 from 0 to 2", MoveType::Pt1).err(),
+            Some(Issue::UnknownStack(0))
+        );
+    }
+
+    #[test]
+    fn test_invalid_moves() {
+        let (_, mut stacks) = create_test_data();
+        let text = "move 1 from 2 to 1\nmove 4 from 1 to 2\nmove 1 from 3 to 1\nmove 1 from 1 to 2";
+
+        let errors = apply_moves(&mut stacks, text, MoveType::Pt1, false);
+        assert_eq!(errors.len(), 1);
+        assert_eq!(
+            errors[0].issue,
+            Issue::NotEnoughCrates {
+                stack: 1,
+                available: 3
+            }
+        );
+        assert_eq!(
+            errors[0].describe(),
+            "line 2: stack 1 has only 3 crates 'move 4 from 1 to 2'"
+        );
+        // nothing is lost, the stacks are the ones before the move
+        assert_eq!(errors[0].stacks, stacks);
+        assert_eq!(get_top_crates(&stacks), "X");
+
+        let (_, mut stacks) = create_test_data();
+        let errors = apply_moves(&mut stacks, text, MoveType::Pt1, true);
+        let lines: Vec<usize> = errors.iter().map(|err| err.line).collect();
+        assert_eq!(lines, vec![2, 3]);
+        assert_eq!(errors[1].issue, Issue::UnknownStack(3));
+        assert_eq!(get_top_crates(&stacks), "BX");
+    }
+
+    #[test]
+    fn test_check() {
+        assert_eq!(check("data/day5/test", false), 0);
+        let (_, errors) = final_stacks("data/day5/test_invalid", MoveType::Pt2, true);
+        let lines: Vec<usize> = errors.iter().map(|err| err.line).collect();
+        assert_eq!(lines, vec![7, 9]);
+        assert_eq!(check("data/day5/test_invalid", false), 2);
+        assert_eq!(check("data/day5/test_invalid", true), 4);
+    }
 }
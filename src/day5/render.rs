@@ -0,0 +1,95 @@
+// Renders stacks as the drawing of the puzzle, the inverse of parse_stacks.
+// Every row has the full width, empty places are filled with spaces. A column
+// is as wide as its widest crate or its number, crates and numbers are
+// centered so that every crate is above its number.
+
+use super::Crate;
+
+pub fn render(stacks: &[Vec<Crate>]) -> String {
+    let height = stacks.iter().map(|s| s.len()).max().unwrap_or(0);
+    let widths: Vec<usize> = stacks
+        .iter()
+        .enumerate()
+        .map(|(i, stack)| {
+            stack
+                .iter()
+                .map(|c| c.label.chars().count() + 2)
+                .fold(3.max((i + 1).to_string().len()), usize::max)
+        })
+        .collect();
+    let mut lines = Vec::new();
+
+    for level in (0..height).rev() {
+        let cells: Vec<String> = stacks
+            .iter()
+            .zip(&widths)
+            .map(|(stack, width)| match stack.get(level) {
+                Some(c) => format!("{:^width$}", format!("[{}]", c.label)),
+                None => " ".repeat(*width),
+            })
+            .collect();
+        lines.push(cells.join(" "));
+    }
+
+    let footer: Vec<String> = widths
+        .iter()
+        .enumerate()
+        .map(|(i, width)| format!("{:^width$}", i + 1))
+        .collect();
+    lines.push(footer.join(" "));
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{parse_stacks, split_file};
+    use std::fs;
+
+    fn stacks(names: &[&str]) -> Vec<Vec<Crate>> {
+        names
+            .iter()
+            .map(|s| s.chars().map(|c| Crate::build(&c.to_string())).collect())
+            .collect()
+    }
+
+    #[test]
+    fn test_render() {
+        let text = render(&stacks(&["ZN", "MCD", "P"]));
+        assert_eq!(text, "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 ");
+    }
+
+    #[test]
+    fn test_render_wide() {
+        let s = vec![
+            vec![Crate::build("ABC"), Crate::build("D")],
+            vec![Crate::build("E")],
+        ];
+        assert_eq!(render(&s), " [D]     \n[ABC] [E]\n  1    2 ");
+    }
+
+    #[test]
+    fn test_round_trip() {
+        for s in [
+            stacks(&["ZN", "MCD", "P"]),
+            stacks(&["", "A", ""]),
+            stacks(&["ABCDEFG", "", "XY", "Q", "", "", "R", "S", "T"]),
+            stacks(&[
+                "A", "", "B", "C", "D", "E", "F", "G", "H", "IJ", "K", "", "L",
+            ]),
+            vec![
+                vec![Crate::build("ABCD"), Crate::build("E")],
+                vec![],
+                vec![Crate::build("XY"), Crate::build("Z")],
+            ],
+        ] {
+            assert_eq!(parse_stacks(&render(&s)), s);
+        }
+
+        // the drawing of the puzzle is reproduced exactly
+        let file_str = fs::read_to_string("data/day5/input").expect("Unable to read file");
+        let (stack_str, _) = split_file(file_str.as_str());
+        assert_eq!(render(&parse_stacks(stack_str)), stack_str);
+    }
+}
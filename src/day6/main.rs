@@ -10,16 +10,16 @@ fn is_start_of_packet(window: &[char]) -> bool {
             set.insert(c);
         }
     }
-    return true;
+    true
 }
 
-fn solve_day6(stream: &Vec<char>, window_size: usize) -> usize {
+fn solve_day6(stream: &[char], window_size: usize) -> usize {
     for (idx, c) in stream.windows(window_size).enumerate() {
         if is_start_of_packet(c) {
             return idx + window_size;
         }
     }
-    return stream.len();
+    stream.len()
 }
 
 fn main() {
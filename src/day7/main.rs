@@ -13,12 +13,12 @@ impl Node {
         let first = it.next().unwrap();
         let name = it.next().unwrap();
         if first == "dir" {
-            return Node::Dir {
+            Node::Dir {
                 name: name.to_string(),
-            };
+            }
         } else {
             let size: usize = first.parse().unwrap();
-            return Node::File { size: size };
+            Node::File { size }
         }
     }
 }
@@ -58,7 +58,7 @@ impl Op {
         let cmd = block_lines.next().unwrap().trim();
         if cmd.starts_with("cd") {
             let cd_dst = CdDst::build(cmd);
-            return Op::Cd(cd_dst);
+            Op::Cd(cd_dst)
         } else if cmd.starts_with("ls") {
             let mut nodes: Nodes = Vec::new();
             for line in block_lines {
@@ -66,8 +66,7 @@ impl Op {
             }
             Op::Ls(nodes)
         } else {
-            assert!(false);
-            Op::Cd(CdDst::Root)
+            unreachable!()
         }
     }
 }
@@ -154,7 +153,7 @@ fn process_directories(input: &str) -> BTreeMap<String, usize> {
         .trim()
         .split('$')
         .filter(|s| !s.is_empty())
-        .map(|b| Op::build(b))
+        .map(Op::build)
         .collect();
 
     // collect directory structure into a tree map
@@ -174,8 +173,7 @@ fn solve_pt2(total_sizes: &BTreeMap<String, usize>) -> usize {
     let used_space = total_sizes["/"];
 
     *total_sizes
-        .iter()
-        .map(|(_, s)| s)
+        .values()
         .filter(|s| TOTAL_SPACE + **s - used_space >= REQUIRED_FREE_SPACE)
         .min()
         .unwrap()
@@ -213,12 +211,12 @@ mod tests {
             Node::File { size } => {
                 assert_eq!(size, 10442123);
             }
-            Node::Dir { name: _ } => assert!(false),
+            Node::Dir { name: _ } => unreachable!(),
         }
 
         let node = Node::build("dir test");
         match node {
-            Node::File { size: _ } => assert!(false),
+            Node::File { size: _ } => unreachable!(),
             Node::Dir { name } => assert_eq!(name, "test"),
         }
     }
@@ -230,27 +228,27 @@ mod tests {
         let cd_dir = CdDst::build("cd dir");
         match cd_dir {
             CdDst::Dir(s) => assert_eq!(s, "dir"),
-            _ => assert!(false),
+            _ => unreachable!(),
         }
 
         match Op::build("cd ..") {
             Op::Cd(cd) => assert_eq!(cd, CdDst::Up),
             Op::Ls(_) => {
-                assert!(false);
+                unreachable!();
             }
         }
 
         match Op::build("ls\n42 file.txt\ndir test_dir") {
-            Op::Cd(_) => assert!(false),
+            Op::Cd(_) => unreachable!(),
             Op::Ls(nodes) => {
                 match &nodes[0] {
                     Node::File { size } => {
                         assert_eq!(*size, 42);
                     }
-                    Node::Dir { name: _ } => assert!(false),
+                    Node::Dir { name: _ } => unreachable!(),
                 }
                 match &nodes[1] {
-                    Node::File { size: _ } => assert!(false),
+                    Node::File { size: _ } => unreachable!(),
                     Node::Dir { name } => assert_eq!(name, "test_dir"),
                 }
             }
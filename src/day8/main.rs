@@ -30,7 +30,7 @@ impl Map {
     }
 
     fn get(&self, x: usize, y: usize) -> u8 {
-        return self.trees[y * self.width + x];
+        self.trees[y * self.width + x]
     }
 
     fn inside(&self, x: i32, y: i32) -> bool {
@@ -159,7 +159,7 @@ mod tests {
     fn test_day8() {
         let test_input = fs::read_to_string("data/day8/test").expect("Unable to read file");
 
-        let map = Map::build(&test_input.as_str());
+        let map = Map::build(test_input.as_str());
         assert_eq!(map.width, 5);
         assert_eq!(map.height, 5);
         assert_eq!(map.trees.len(), 25);
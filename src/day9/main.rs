@@ -36,7 +36,7 @@ impl Move {
         };
         let num = line_vec[1].parse::<i32>().unwrap();
 
-        Move { dir: dir, num: num }
+        Move { dir, num }
     }
 
     fn move_head(&self, p: &mut Point) {
@@ -64,7 +64,7 @@ impl Move {
 }
 
 fn build_moves(input: &str) -> Vec<Move> {
-    input.lines().map(|l| Move::build(l)).collect()
+    input.lines().map(Move::build).collect()
 }
 
 fn solve(moves: &[Move], num_knots: usize) -> usize {
@@ -83,7 +83,7 @@ fn solve(moves: &[Move], num_knots: usize) -> usize {
                 let (left, right) = heads.split_at_mut(i);
                 Move::move_tail(left.last().unwrap(), right.first_mut().unwrap());
             }
-            tails.insert(heads.last().unwrap().clone());
+            tails.insert(*heads.last().unwrap());
         }
     }
     tails.len()